@@ -1,13 +1,824 @@
-use xors::board::BoardToken;
-use xors::player::InteractivePlayer;
-use xors::RandomGame;
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use std::collections::HashMap;
+use std::env;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+use xors::board::{Board, BoardLocation, BoardToken};
+use xors::gomoku::{GomokuAi, GomokuRules};
+use xors::grid::Grid;
+use xors::notakto::{Notakto, NotaktoAi};
+use xors::notation::Position;
+use xors::numerical::Numerical;
+use xors::player::{
+    AiPlayer, Difficulty, InteractivePlayer, MinimaxPlayer, Player, RandomPlayer, ScriptedPlayer,
+};
+use xors::record::GameRecord;
+use xors::replay::Replay;
+use xors::rules::RuleSet;
+use xors::search::SearchEngine;
+use xors::ultimate::UltimateBoard;
+use xors::wild::Wild;
+use xors::{Game, GameResult, MovePolicy, Turn};
 
-fn main() {
-    let mut game = RandomGame::new();
-    let player1 = InteractivePlayer::new("Yasmin", BoardToken::Cross);
-    //let player2 = InteractivePlayer::new("Mummy", BoardToken::Nought);
+const USAGE: &str = "Usage: xors-cli <command> [options]
+
+Commands:
+  play                  Play a game (human against random by default)
+  analyze <position>    Analyse a position, e.g. \"X.O/.X./..O o\"
+  replay <file>         Step through a saved game
+  selfplay              Let two computer players play each other
+  grid                  Play on a bigger board, e.g. four in a row on 4x4
+  gomoku                Five in a row on a 15x15 board
+  ultimate              Nine boards in one, where each move picks the
+                        opponent's next board
+  notakto               Both players play X and whoever makes three in a row
+                        loses
+  wild                  Either player may play X or O, and whoever makes
+                        three in a row wins
+  numerical             Odd numbers against even, and whoever completes a
+                        line adding up to 15 wins
+  tournament            Play every computer player against every other
+  help                  Show this message
+
+Player kinds:
+  human, random, weighted (random, preferring the centre and corners),
+  easy, medium, hard, perfect, minimax,
+  scripted:<locations> (e.g. scripted:5,1,9)
+
+Options for play and selfplay:
+  --p1 <kind>           Player one (play: human, selfplay: perfect)
+  --p2 <kind>           Player two (default: random)
+  --p1-name <name>      Name for player one
+  --p2-name <name>      Name for player two
+  --p1-token <x|o>      Token for player one, player two gets the other (default: x)
+  --first <1|2>         Which player moves first (default: 1)
+  --illegal <policy>    On an occupied location: forfeit, skip or retry:<n>
+                        (default: retry:3)
+  --exhausted <policy>  When a player runs out of moves (default: forfeit)
+  --save <file>         Save the game record when the game ends. With more
+                        than one game, each is saved with its number added,
+                        e.g. games-2.txt
+  --resume <file>       Carry on from a saved game (play only)
+  --games <n>           Number of games to play (selfplay only, default: 1)
+  --seed <n>            Seed for the computer players' random choices
+                        (default: picked at random and shown)
+  --rules <rules>       standard, or misere where three in a row loses
+                        (default: standard, or the saved game's rules)
+
+Options for grid:
+  --size <n>            Width and height of the board (default: 4)
+  --win <n>             How many in a row wins (default: the size, at most 5)
+  --p1 <kind>           Player one, who plays X: human, random or ai
+                        (default: human)
+  --p2 <kind>           Player two, who plays O (default: random)
+  --p1-name, --p2-name, --seed as for play
+
+Options for gomoku:
+  --rules <rules>       freestyle (five or more wins) or standard (exactly five)
+                        (default: freestyle)
+  --p1, --p2            As for grid, but player two defaults to ai
+  --p1-name, --p2-name, --seed as for play
+
+Options for ultimate:
+  --p1 <kind>           Player one, who plays X: human or random (default: human)
+  --p2 <kind>           Player two, who plays O (default: random)
+  --p1-name, --p2-name, --seed as for play
+
+Options for notakto:
+  --boards <n>          How many boards to play on, 1 to 9 (default: 1)
+  --p1 <kind>           Player one: human, random or ai (default: human)
+  --p2 <kind>           Player two (default: ai)
+  --p1-name, --p2-name, --seed as for play
+
+Options for wild:
+  --p1 <kind>           Player one: human or random (default: human)
+  --p2 <kind>           Player two (default: random)
+  --p1-name, --p2-name, --seed as for play
+  A human move is a location and a token, e.g. 5x or 5o.
+
+Options for numerical:
+  --p1 <kind>           Player one, who plays 1 3 5 7 9: human or random
+                        (default: human)
+  --p2 <kind>           Player two, who plays 2 4 6 8 (default: random)
+  --p1-name, --p2-name, --seed as for play
+  A human move is a cell and a number, e.g. 5 3.
+
+Options for analyze:
+  --rules <rules>       As for play
+
+Options for replay:
+  --all                 Print every position instead of stepping through
+
+Options for tournament:
+  --players <kinds>     Comma separated player kinds (default: random,easy,medium,hard,perfect)
+  --games <n>           Games per pairing, sides alternate (default: 2)
+  --seed <n>            Seed for the computer players' random choices
+  --rules <rules>       As for play";
+
+const EXIT_FAILURE: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+
+struct Options {
+    positional: Vec<String>,
+    flags: HashMap<String, String>,
+}
+
+impl Options {
+    fn parse(args: &[String], switches: &[&str]) -> Result<Self, String> {
+        let mut positional = Vec::new();
+        let mut flags = HashMap::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                if switches.contains(&name) {
+                    flags.insert(name.to_string(), String::new());
+                } else {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("Missing value for --{}", name))?;
+                    flags.insert(name.to_string(), value.clone());
+                }
+            } else {
+                positional.push(arg.clone());
+            }
+        }
+        Ok(Self { positional, flags })
+    }
+
+    fn check(&self, allowed: &[&str], max_positional: usize) -> Result<(), String> {
+        if let Some(flag) = self
+            .flags
+            .keys()
+            .find(|flag| !allowed.contains(&flag.as_str()))
+        {
+            return Err(format!("Unknown option --{}", flag));
+        }
+        if self.positional.len() > max_positional {
+            return Err(format!(
+                "Unexpected argument {}",
+                self.positional[max_positional]
+            ));
+        }
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.flags.get(name).map(String::as_str)
+    }
+
+    // The --seed option, falling back to `default` and then to a fresh random
+    // seed, so that every game has a seed that can be shown and reused.
+    fn seed(&self, default: Option<u64>) -> Result<u64, String> {
+        match self.get("seed") {
+            None => Ok(default.unwrap_or_else(|| thread_rng().gen())),
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("--seed expects a number, got {}", value)),
+        }
+    }
+
+    fn number(&self, name: &str, default: usize) -> Result<usize, String> {
+        match self.get(name) {
+            None => Ok(default),
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("--{} expects a number, got {}", name, value)),
+        }
+    }
+}
+
+fn parse_token(value: &str) -> Result<BoardToken, String> {
+    match value {
+        "x" | "X" => Ok(BoardToken::Cross),
+        "o" | "O" => Ok(BoardToken::Nought),
+        _ => Err(format!("Unknown token {}, expected x or o", value)),
+    }
+}
+
+fn parse_kind(kind: &str) -> Result<(), String> {
+    match kind {
+        "human" | "random" | "weighted" | "easy" | "medium" | "hard" | "perfect" | "minimax" => {
+            Ok(())
+        }
+        _ if kind.starts_with("scripted:") => parse_script(kind).map(|_| ()),
+        _ => Err(format!("Unknown player kind {}", kind)),
+    }
+}
+
+fn parse_script(kind: &str) -> Result<Vec<BoardLocation>, String> {
+    kind.trim_start_matches("scripted:")
+        .split(',')
+        .map(|number| {
+            number
+                .trim()
+                .parse()
+                .ok()
+                .and_then(BoardLocation::from_number)
+                .ok_or_else(|| format!("Bad scripted location {}", number))
+        })
+        .collect()
+}
+
+fn parse_rules(options: &Options) -> Result<RuleSet, String> {
+    let name = options.get("rules").unwrap_or("standard");
+    RuleSet::from_name(name)
+        .ok_or_else(|| format!("Unknown rules {}, expected standard or misere", name))
+}
+
+fn parse_policy(value: &str) -> Result<MovePolicy, String> {
+    match value {
+        "forfeit" => Ok(MovePolicy::Forfeit),
+        "skip" => Ok(MovePolicy::Skip),
+        _ => value
+            .strip_prefix("retry:")
+            .and_then(|limit| limit.parse().ok())
+            .map(MovePolicy::Retry)
+            .ok_or_else(|| format!("Unknown policy {}", value)),
+    }
+}
+
+fn make_ai(name: &str, token: BoardToken, difficulty: Difficulty, seed: u64) -> Box<dyn Player> {
+    let mut player = AiPlayer::new(name, token, difficulty);
+    player.set_seed(seed);
+    Box::new(player)
+}
+
+fn make_random(name: &str, token: BoardToken, weights: [u32; 9], seed: u64) -> Box<dyn Player> {
+    let mut player = RandomPlayer::new_weighted(name, token, weights);
+    player.set_seed(seed);
+    Box::new(player)
+}
+
+fn make_player(
+    kind: &str,
+    name: &str,
+    token: BoardToken,
+    seed: u64,
+) -> Result<Box<dyn Player>, String> {
+    Ok(match kind {
+        "human" => Box::new(InteractivePlayer::new(name, token)),
+        "random" => make_random(name, token, RandomPlayer::UNIFORM, seed),
+        "weighted" => make_random(name, token, RandomPlayer::CENTRE_AND_CORNERS, seed),
+        "easy" => make_ai(name, token, Difficulty::Easy, seed),
+        "medium" => make_ai(name, token, Difficulty::Medium, seed),
+        "hard" => make_ai(name, token, Difficulty::Hard, seed),
+        "perfect" => make_ai(name, token, Difficulty::Perfect, seed),
+        "minimax" => Box::new(MinimaxPlayer::new(name, token)),
+        _ => {
+            parse_kind(kind)?;
+            Box::new(ScriptedPlayer::new(name, token, &parse_script(kind)?))
+        }
+    })
+}
+
+fn register(game: &mut Game, player: Box<dyn Player>) -> Result<(), String> {
+    game.register_player(player)
+        .map_err(|err| format!("Could not register player: {}", err))
+}
+
+// Each player gets their own seed, derived from the game's.
+fn player_seeds(seed: u64) -> (u64, u64) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (rng.gen(), rng.gen())
+}
+
+// Builds a game from the --p1/--p2 family of options. Whoever moves first is
+// registered first.
+fn setup_game(options: &Options, default_p1: &str, seed: u64) -> Result<Game, String> {
+    let kind1 = options.get("p1").unwrap_or(default_p1);
+    let kind2 = options.get("p2").unwrap_or("random");
+    parse_kind(kind1)?;
+    parse_kind(kind2)?;
+    let name1 = options.get("p1-name").unwrap_or(kind1);
+    let name2 = options.get("p2-name").unwrap_or(kind2);
+    let token1 = parse_token(options.get("p1-token").unwrap_or("x"))?;
+    let token2 = token1.opponent();
+    let (seed1, seed2) = player_seeds(seed);
+    let player1 = make_player(kind1, name1, token1, seed1)?;
+    let player2 = make_player(kind2, name2, token2, seed2)?;
+
+    let (first, second) = match options.get("first").unwrap_or("1") {
+        "1" => (player1, player2),
+        "2" => (player2, player1),
+        other => return Err(format!("--first expects 1 or 2, got {}", other)),
+    };
+    let mut game = Game::new();
+    game.set_seed(seed);
+    game.set_rules(parse_rules(options)?);
+    register(&mut game, first)?;
+    register(&mut game, second)?;
+    if let Some(policy) = options.get("illegal") {
+        game.set_illegal_move_policy(parse_policy(policy)?);
+    }
+    if let Some(policy) = options.get("exhausted") {
+        game.set_exhausted_policy(parse_policy(policy)?);
+    }
+    Ok(game)
+}
 
-    game.register_player(player1);
-    //game.register_player(player2);
-    let _result = game.play();
+fn save(game: &Game, path: &str) -> Result<(), String> {
+    game.record()
+        .save(path)
+        .map_err(|err| format!("Could not save game to {}: {}", path, err))?;
+    println!("Game saved to {}", path);
+    Ok(())
+}
+
+// Puts the game number before any extension, so games.txt becomes games-3.txt.
+fn numbered_path(path: &str, number: usize) -> String {
+    let path = Path::new(path);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, number, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, number),
+    };
+    path.with_file_name(name).display().to_string()
+}
+
+fn play(options: &Options) -> Result<(), String> {
+    let record = match options.get("resume") {
+        Some(path) => Some((
+            path,
+            GameRecord::load(path)
+                .map_err(|err| format!("Could not read game record {}: {}", path, err))?,
+        )),
+        None => None,
+    };
+    // A resumed game carries on with its recorded seed unless told otherwise.
+    let seed = options.seed(record.as_ref().and_then(|(_, record)| record.seed))?;
+    let mut game = setup_game(options, "human", seed)?;
+    if let Some((path, record)) = record {
+        game.resume(&record)
+            .map_err(|err| format!("Could not resume game from {}: {}", path, err))?;
+        game.set_seed(seed);
+    }
+    let result = game.play();
+    if let Some(path) = options.get("save") {
+        save(&game, path)?;
+    }
+    result
+        .map(|_| ())
+        .map_err(|err| format!("The game could not be finished: {}", err))
+}
+
+fn selfplay(options: &Options) -> Result<(), String> {
+    let games = options.number("games", 1)?;
+    let seed = options.seed(None)?;
+    let (mut wins1, mut wins2, mut ties) = (0, 0, 0);
+    for n in 0..games {
+        let mut game = setup_game(options, "perfect", seed.wrapping_add(n as u64))?;
+        game.set_verbose(games == 1);
+        let result = game
+            .play()
+            .map_err(|err| format!("The game could not be finished: {}", err))?;
+        match result {
+            GameResult::Player1(_) => wins1 += 1,
+            GameResult::Player2(_) => wins2 += 1,
+            GameResult::Tie => ties += 1,
+        }
+        if let Some(path) = options.get("save") {
+            if games > 1 {
+                save(&game, &numbered_path(path, n + 1))?;
+            } else {
+                save(&game, path)?;
+            }
+        }
+    }
+    if games > 1 {
+        println!("Random seed: {}", seed);
+        println!(
+            "First player won {}, second player won {}, tied {}",
+            wins1, wins2, ties
+        );
+    }
+    Ok(())
+}
+
+// The players' tokens on boards where each has their own, and on those where
+// their turns tell them apart.
+const CROSS_FIRST: [BoardToken; 2] = [BoardToken::Cross, BoardToken::Nought];
+const TURNS: [Turn; 2] = [Turn::Player1, Turn::Player2];
+
+// Builds a computer player for a board, given a name, token and seed. It is
+// `None` for boards without a computer player of their own.
+type MakeAi<B> = fn(&str, <B as Board>::Token, u64) -> Box<dyn Player<B>>;
+
+fn make_board_player<B: Board + 'static>(
+    kind: &str,
+    name: &str,
+    token: B::Token,
+    seed: u64,
+    make_ai: Option<MakeAi<B>>,
+) -> Result<Box<dyn Player<B>>, String> {
+    match (kind, make_ai) {
+        ("human", _) => Ok(Box::new(InteractivePlayer::new(name, token))),
+        ("random", _) => {
+            let mut player = RandomPlayer::new(name, token);
+            player.set_seed(seed);
+            Ok(Box::new(player))
+        }
+        ("ai", Some(make_ai)) => Ok(make_ai(name, token, seed)),
+        _ => Err(format!("Unknown player kind {}", kind)),
+    }
+}
+
+fn gomoku_ai(name: &str, token: BoardToken, seed: u64) -> Box<dyn Player<Grid>> {
+    let mut player = GomokuAi::new(name, token);
+    player.set_seed(seed);
+    Box::new(player)
+}
+
+fn grid(options: &Options) -> Result<(), String> {
+    let size = options.number("size", 4)?;
+    let win = options.number("win", size.min(5))?;
+    let grid = Grid::new(size, size, win).map_err(|err| format!("Bad board: {}", err))?;
+    play_board(options, grid, CROSS_FIRST, "random", Some(gomoku_ai))
+}
+
+fn gomoku(options: &Options) -> Result<(), String> {
+    let rules = match options.get("rules").unwrap_or("freestyle") {
+        "freestyle" => GomokuRules::Freestyle,
+        "standard" => GomokuRules::Standard,
+        other => return Err(format!("Unknown gomoku rules {}", other)),
+    };
+    play_board(options, rules.grid(), CROSS_FIRST, "ai", Some(gomoku_ai))
+}
+
+// Plays a game on `board` between the players chosen in the options, who
+// hold `tokens` in order.
+fn play_board<B: Board + 'static>(
+    options: &Options,
+    board: B,
+    tokens: [B::Token; 2],
+    default_p2: &str,
+    make_ai: Option<MakeAi<B>>,
+) -> Result<(), String> {
+    let kind1 = options.get("p1").unwrap_or("human");
+    let kind2 = options.get("p2").unwrap_or(default_p2);
+    let seed = options.seed(None)?;
+    let (seed1, seed2) = player_seeds(seed);
+    let [token1, token2] = tokens;
+    let player1 = make_board_player(
+        kind1,
+        options.get("p1-name").unwrap_or(kind1),
+        token1,
+        seed1,
+        make_ai,
+    )?;
+    let player2 = make_board_player(
+        kind2,
+        options.get("p2-name").unwrap_or(kind2),
+        token2,
+        seed2,
+        make_ai,
+    )?;
+
+    let mut game = Game::new_with_board(board);
+    for player in [player1, player2] {
+        game.register_player(player)
+            .map_err(|err| format!("Could not register player: {}", err))?;
+    }
+    game.set_seed(seed);
+    game.play()
+        .map(|_| ())
+        .map_err(|err| format!("The game could not be finished: {}", err))
+}
+
+fn notakto(options: &Options) -> Result<(), String> {
+    let boards = options.number("boards", 1)?;
+    let state = Notakto::new(boards).map_err(|err| format!("Bad board: {}", err))?;
+    play_board(
+        options,
+        state,
+        TURNS,
+        "ai",
+        Some(|name, token, seed| {
+            let mut player = NotaktoAi::new(name, token);
+            player.set_seed(seed);
+            Box::new(player)
+        }),
+    )
+}
+
+struct Standing<'a> {
+    name: &'a str,
+    won: usize,
+    drawn: usize,
+    lost: usize,
+}
+
+impl Standing<'_> {
+    // Two points for a win and one for a tie.
+    fn points(&self) -> usize {
+        self.won * 2 + self.drawn
+    }
+}
+
+fn tournament(options: &Options) -> Result<(), String> {
+    let players: Vec<&str> = options
+        .get("players")
+        .unwrap_or("random,easy,medium,hard,perfect")
+        .split(',')
+        .collect();
+    for kind in &players {
+        if *kind == "human" {
+            return Err("Humans can't enter the tournament".into());
+        }
+        parse_kind(kind)?;
+    }
+    let games = options.number("games", 2)?;
+    let rules = parse_rules(options)?;
+    let mut seed = options.seed(None)?;
+    println!("Random seed: {}", seed);
+
+    let mut standings: Vec<Standing> = players
+        .iter()
+        .map(|name| Standing {
+            name,
+            won: 0,
+            drawn: 0,
+            lost: 0,
+        })
+        .collect();
+    for i in 0..players.len() {
+        for j in (i + 1)..players.len() {
+            for n in 0..games {
+                let (first, second) = if n % 2 == 0 { (i, j) } else { (j, i) };
+                let (seed1, seed2) = player_seeds(seed);
+                seed = seed.wrapping_add(1);
+                let mut game = Game::new();
+                game.set_verbose(false);
+                game.set_rules(rules);
+                register(
+                    &mut game,
+                    make_player(players[first], players[first], BoardToken::Cross, seed1)?,
+                )?;
+                register(
+                    &mut game,
+                    make_player(players[second], players[second], BoardToken::Nought, seed2)?,
+                )?;
+                let result = game
+                    .play()
+                    .map_err(|err| format!("The game could not be finished: {}", err))?;
+                match result {
+                    GameResult::Player1(_) => {
+                        standings[first].won += 1;
+                        standings[second].lost += 1;
+                    }
+                    GameResult::Player2(_) => {
+                        standings[second].won += 1;
+                        standings[first].lost += 1;
+                    }
+                    GameResult::Tie => {
+                        standings[first].drawn += 1;
+                        standings[second].drawn += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    standings.sort_by_key(|standing| std::cmp::Reverse(standing.points()));
+    println!(
+        "{:<20} {:>4} {:>4} {:>4} {:>6}",
+        "Player", "W", "D", "L", "Points"
+    );
+    for standing in standings {
+        println!(
+            "{:<20} {:>4} {:>4} {:>4} {:>6}",
+            standing.name,
+            standing.won,
+            standing.drawn,
+            standing.lost,
+            standing.points()
+        );
+    }
+    Ok(())
+}
+
+fn describe_score(score: i32, to_move: &BoardToken) -> String {
+    if score == 0 {
+        "Draw with best play".into()
+    } else if score > 0 {
+        format!("{} wins with best play", to_move)
+    } else {
+        format!("{} wins with best play", to_move.opponent())
+    }
+}
+
+fn analyze(options: &Options) -> Result<(), String> {
+    let text = options
+        .positional
+        .first()
+        .ok_or("analyze needs a position, e.g. \"X.O/.X./..O o\"")?;
+    let position: Position = text
+        .parse()
+        .map_err(|err| format!("Bad position {}: {}", text, err))?;
+    let mut engine = SearchEngine::new_with_rules(parse_rules(options)?);
+    let result = engine.search(&position.board, position.to_move.clone());
+
+    print!("{}", position.board);
+    println!("{} to move", position.to_move);
+    println!("{}", describe_score(result.score, &position.to_move));
+    if let Some(best_move) = &result.best_move {
+        println!("Best move: {}", best_move.number());
+    }
+    for location in position.board.legal_moves() {
+        let mut board = position.board.clone();
+        board
+            .play(location.clone(), position.to_move.clone())
+            .expect("legal moves are free");
+        let reply = engine.search(&board, position.to_move.opponent());
+        println!(
+            "  {}: {}",
+            location.number(),
+            describe_score(-reply.score, &position.to_move)
+        );
+    }
+    println!(
+        "Searched {} nodes ({} table hits) in {:?}",
+        result.stats.nodes, result.stats.table_hits, result.stats.elapsed
+    );
+    Ok(())
+}
+
+fn show(replay: &Replay) {
+    print!("{}", replay.board());
+    println!("{}", replay.annotation());
+}
+
+fn replay(options: &Options) -> Result<(), String> {
+    let path = options
+        .positional
+        .first()
+        .ok_or("replay needs a game record file")?;
+    let mut replay = GameRecord::load(path)
+        .and_then(Replay::new)
+        .map_err(|err| format!("Could not read game record {}: {}", path, err))?;
+
+    if options.get("all").is_some() {
+        loop {
+            show(&replay);
+            if !replay.step_forward() {
+                return Ok(());
+            }
+        }
+    }
+
+    loop {
+        show(&replay);
+        print!("n(ext), p(rev), f(irst), l(ast), move number or q(uit): ");
+        io::stdout().flush().expect("Error writing to screen");
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).unwrap_or(0) == 0 {
+            return Ok(());
+        }
+        match input.trim() {
+            "" | "n" => {
+                if !replay.step_forward() {
+                    println!("Already at the last move.");
+                }
+            }
+            "p" => {
+                if !replay.step_back() {
+                    println!("Already at the start.");
+                }
+            }
+            "f" => replay.to_first(),
+            "l" => replay.to_last(),
+            "q" => return Ok(()),
+            other => match other.parse() {
+                Ok(number) if replay.jump_to(number) => {}
+                _ => println!("Sorry. Didn't understand that. Try again."),
+            },
+        }
+    }
+}
+
+fn run(command: &str, args: &[String]) -> Result<(), (i32, String)> {
+    let usage = |message: String| (EXIT_USAGE, message);
+    let failure = |message: String| (EXIT_FAILURE, message);
+    let game_options = [
+        "p1",
+        "p2",
+        "p1-name",
+        "p2-name",
+        "p1-token",
+        "first",
+        "illegal",
+        "exhausted",
+        "save",
+        "seed",
+        "rules",
+    ];
+    match command {
+        "play" => {
+            let options = Options::parse(args, &[]).map_err(usage)?;
+            let mut allowed = game_options.to_vec();
+            allowed.push("resume");
+            options.check(&allowed, 0).map_err(usage)?;
+            play(&options).map_err(failure)
+        }
+        "selfplay" => {
+            let options = Options::parse(args, &[]).map_err(usage)?;
+            let mut allowed = game_options.to_vec();
+            allowed.push("games");
+            options.check(&allowed, 0).map_err(usage)?;
+            if options.get("p1") == Some("human") || options.get("p2") == Some("human") {
+                return Err(usage("selfplay is for computer players only".into()));
+            }
+            selfplay(&options).map_err(failure)
+        }
+        "grid" => {
+            let options = Options::parse(args, &[]).map_err(usage)?;
+            options
+                .check(
+                    &["size", "win", "p1", "p2", "p1-name", "p2-name", "seed"],
+                    0,
+                )
+                .map_err(usage)?;
+            grid(&options).map_err(failure)
+        }
+        "gomoku" => {
+            let options = Options::parse(args, &[]).map_err(usage)?;
+            options
+                .check(&["rules", "p1", "p2", "p1-name", "p2-name", "seed"], 0)
+                .map_err(usage)?;
+            gomoku(&options).map_err(failure)
+        }
+        "ultimate" => {
+            let options = Options::parse(args, &[]).map_err(usage)?;
+            options
+                .check(&["p1", "p2", "p1-name", "p2-name", "seed"], 0)
+                .map_err(usage)?;
+            play_board(
+                &options,
+                UltimateBoard::default(),
+                CROSS_FIRST,
+                "random",
+                None,
+            )
+            .map_err(failure)
+        }
+        "notakto" => {
+            let options = Options::parse(args, &[]).map_err(usage)?;
+            options
+                .check(&["boards", "p1", "p2", "p1-name", "p2-name", "seed"], 0)
+                .map_err(usage)?;
+            notakto(&options).map_err(failure)
+        }
+        "wild" => {
+            let options = Options::parse(args, &[]).map_err(usage)?;
+            options
+                .check(&["p1", "p2", "p1-name", "p2-name", "seed"], 0)
+                .map_err(usage)?;
+            play_board(&options, Wild::default(), TURNS, "random", None).map_err(failure)
+        }
+        "numerical" => {
+            let options = Options::parse(args, &[]).map_err(usage)?;
+            options
+                .check(&["p1", "p2", "p1-name", "p2-name", "seed"], 0)
+                .map_err(usage)?;
+            play_board(&options, Numerical::default(), TURNS, "random", None).map_err(failure)
+        }
+        "tournament" => {
+            let options = Options::parse(args, &[]).map_err(usage)?;
+            options
+                .check(&["players", "games", "seed", "rules"], 0)
+                .map_err(usage)?;
+            tournament(&options).map_err(failure)
+        }
+        "analyze" => {
+            let options = Options::parse(args, &[]).map_err(usage)?;
+            options.check(&["rules"], 1).map_err(usage)?;
+            analyze(&options).map_err(failure)
+        }
+        "replay" => {
+            let options = Options::parse(args, &["all"]).map_err(usage)?;
+            options.check(&["all"], 1).map_err(usage)?;
+            replay(&options).map_err(failure)
+        }
+        _ => Err(usage(format!("Unknown command {}", command))),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some(command) = args.first() else {
+        eprintln!("{}", USAGE);
+        process::exit(EXIT_USAGE);
+    };
+    if matches!(command.as_str(), "help" | "--help" | "-h") || args.iter().any(|a| a == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+    if let Err((code, message)) = run(command, &args[1..]) {
+        eprintln!("{}", message);
+        if code == EXIT_USAGE {
+            eprintln!("Run xors-cli --help for usage.");
+        }
+        process::exit(code);
+    }
 }
@@ -1,4 +1,8 @@
+use crate::grid::{Cell, Grid};
+use crate::player::PlayerError;
+use crate::rules::RuleSet;
 use std::fmt;
+use std::str::FromStr;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum BoardToken {
@@ -6,7 +10,7 @@ pub enum BoardToken {
     Cross,
 }
 
-#[derive(Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum BoardLocation {
     TopLeft,
     TopCentre,
@@ -19,172 +23,312 @@ pub enum BoardLocation {
     BottomRight,
 }
 
-#[derive(Default, Debug)]
+#[derive(Debug, PartialEq, Clone)]
+pub enum BoardLine {
+    TopRow,
+    MiddleRow,
+    BottomRow,
+    LeftColumn,
+    CentreColumn,
+    RightColumn,
+    LeftRightDiagonal,
+    RightLeftDiagonal,
+}
+
+// The classic board: a 3x3 grid won with three in a row, addressed by
+// `BoardLocation`.
+#[derive(Debug, PartialEq, Clone)]
 pub struct GameBoard {
-    top_left: Option<BoardToken>,
-    top_centre: Option<BoardToken>,
-    top_right: Option<BoardToken>,
-    middle_left: Option<BoardToken>,
-    middle_centre: Option<BoardToken>,
-    middle_right: Option<BoardToken>,
-    bottom_left: Option<BoardToken>,
-    bottom_centre: Option<BoardToken>,
-    bottom_right: Option<BoardToken>,
+    grid: Grid,
+}
+
+impl Default for GameBoard {
+    fn default() -> Self {
+        Self {
+            grid: Grid::new(3, 3, 3).expect("3x3 is a valid grid"),
+        }
+    }
+}
+
+impl BoardLocation {
+    pub const ALL: [BoardLocation; 9] = [
+        BoardLocation::TopLeft,
+        BoardLocation::TopCentre,
+        BoardLocation::TopRight,
+        BoardLocation::MiddleLeft,
+        BoardLocation::MiddleCentre,
+        BoardLocation::MiddleRight,
+        BoardLocation::BottomLeft,
+        BoardLocation::BottomCentre,
+        BoardLocation::BottomRight,
+    ];
+
+    pub fn index(&self) -> usize {
+        self.clone() as usize
+    }
+
+    pub fn number(&self) -> u32 {
+        self.index() as u32 + 1
+    }
+
+    pub fn from_number(number: u32) -> Option<BoardLocation> {
+        match number {
+            1..=9 => Some(BoardLocation::ALL[number as usize - 1].clone()),
+            _ => None,
+        }
+    }
+
+    pub fn cell(&self) -> Cell {
+        Cell::new(self.index() / 3, self.index() % 3)
+    }
+
+    pub fn from_cell(cell: Cell) -> Option<BoardLocation> {
+        if cell.row < 3 && cell.column < 3 {
+            Some(BoardLocation::ALL[cell.row * 3 + cell.column].clone())
+        } else {
+            None
+        }
+    }
+}
+
+impl BoardLine {
+    pub const ALL: [BoardLine; 8] = [
+        BoardLine::TopRow,
+        BoardLine::MiddleRow,
+        BoardLine::BottomRow,
+        BoardLine::LeftColumn,
+        BoardLine::CentreColumn,
+        BoardLine::RightColumn,
+        BoardLine::LeftRightDiagonal,
+        BoardLine::RightLeftDiagonal,
+    ];
+
+    pub fn locations(&self) -> [BoardLocation; 3] {
+        match self {
+            BoardLine::TopRow => [
+                BoardLocation::TopLeft,
+                BoardLocation::TopCentre,
+                BoardLocation::TopRight,
+            ],
+            BoardLine::MiddleRow => [
+                BoardLocation::MiddleLeft,
+                BoardLocation::MiddleCentre,
+                BoardLocation::MiddleRight,
+            ],
+            BoardLine::BottomRow => [
+                BoardLocation::BottomLeft,
+                BoardLocation::BottomCentre,
+                BoardLocation::BottomRight,
+            ],
+            BoardLine::LeftColumn => [
+                BoardLocation::TopLeft,
+                BoardLocation::MiddleLeft,
+                BoardLocation::BottomLeft,
+            ],
+            BoardLine::CentreColumn => [
+                BoardLocation::TopCentre,
+                BoardLocation::MiddleCentre,
+                BoardLocation::BottomCentre,
+            ],
+            BoardLine::RightColumn => [
+                BoardLocation::TopRight,
+                BoardLocation::MiddleRight,
+                BoardLocation::BottomRight,
+            ],
+            BoardLine::LeftRightDiagonal => [
+                BoardLocation::TopLeft,
+                BoardLocation::MiddleCentre,
+                BoardLocation::BottomRight,
+            ],
+            BoardLine::RightLeftDiagonal => [
+                BoardLocation::TopRight,
+                BoardLocation::MiddleCentre,
+                BoardLocation::BottomLeft,
+            ],
+        }
+    }
+
+    // What `get` finds at each of the line's locations, so the same lines can
+    // be checked on boards holding something other than tokens.
+    pub fn contents<T>(&self, get: impl FnMut(BoardLocation) -> T) -> [T; 3] {
+        self.locations().map(get)
+    }
+}
+
+impl BoardToken {
+    pub fn opponent(&self) -> BoardToken {
+        match self {
+            BoardToken::Nought => BoardToken::Cross,
+            BoardToken::Cross => BoardToken::Nought,
+        }
+    }
+}
+
+// Anything a `Game` can be played on: the classic board, a `Grid` of any
+// size, or a variant's board. Tokens are whatever tells the two players
+// apart, and moves are shown and typed in the board's own notation.
+// Displaying the board shows it.
+pub trait Board: Clone + PartialEq + fmt::Debug + fmt::Display {
+    type Token: Clone + PartialEq + fmt::Debug + fmt::Display;
+    type Move: Clone + PartialEq + fmt::Debug + fmt::Display + FromStr;
+    type Line: Clone + PartialEq + fmt::Debug;
+    type Error: fmt::Debug + fmt::Display;
+
+    // The moves the player holding `token` could make now.
+    fn legal_moves(&self, token: &Self::Token) -> Vec<Self::Move>;
+    fn play_move(&mut self, token: &Self::Token, mv: &Self::Move) -> Result<(), Self::Error>;
+
+    // The token that has won under `rules` and the lines that decided it, if
+    // the game has been won.
+    fn won_by(&self, rules: RuleSet) -> Option<(Self::Token, Vec<Self::Line>)>;
+
+    // Whether nobody can move any more. Only asked once nobody has won, so a
+    // finished board is a tie.
+    fn is_finished(&self) -> bool;
+
+    // Tells an interactive player how to type a move.
+    fn move_prompt(&self, token: &Self::Token) -> String;
+
+    // Where `mv` falls in a `RandomPlayer`'s weights. Boards without a fixed
+    // set of nine locations leave every move equally likely.
+    fn weight_index(&self, _mv: &Self::Move) -> Option<usize> {
+        None
+    }
 }
 
+#[derive(Debug)]
 pub enum BoardError {
     BoardLocationOccupied(BoardLocation),
+    OutOfTurn(BoardToken),
 }
 
 impl GameBoard {
+    // Either token may open, but after that they must alternate: a token
+    // that already outnumbers the other can only be played out of turn.
     pub fn play(&mut self, location: BoardLocation, player: BoardToken) -> Result<(), BoardError> {
-        match location {
-            BoardLocation::TopLeft => {
-                if self.top_left.is_none() {
-                    self.top_left = Some(player);
-                } else {
-                    return Err(BoardError::BoardLocationOccupied(BoardLocation::TopLeft));
-                }
-            }
-            BoardLocation::TopCentre => {
-                if self.top_centre.is_none() {
-                    self.top_centre = Some(player);
-                } else {
-                    return Err(BoardError::BoardLocationOccupied(BoardLocation::TopCentre));
-                }
-            }
-            BoardLocation::TopRight => {
-                if self.top_right.is_none() {
-                    self.top_right = Some(player);
-                } else {
-                    return Err(BoardError::BoardLocationOccupied(BoardLocation::TopRight));
-                }
-            }
-            BoardLocation::MiddleLeft => {
-                if self.middle_left.is_none() {
-                    self.middle_left = Some(player);
-                } else {
-                    return Err(BoardError::BoardLocationOccupied(BoardLocation::MiddleLeft));
-                }
-            }
-            BoardLocation::MiddleCentre => {
-                if self.middle_centre.is_none() {
-                    self.middle_centre = Some(player);
-                } else {
-                    return Err(BoardError::BoardLocationOccupied(
-                        BoardLocation::MiddleCentre,
-                    ));
-                }
-            }
-            BoardLocation::MiddleRight => {
-                if self.middle_right.is_none() {
-                    self.middle_right = Some(player);
-                } else {
-                    return Err(BoardError::BoardLocationOccupied(
-                        BoardLocation::MiddleRight,
-                    ));
-                }
-            }
-            BoardLocation::BottomLeft => {
-                if self.bottom_left.is_none() {
-                    self.bottom_left = Some(player);
-                } else {
-                    return Err(BoardError::BoardLocationOccupied(BoardLocation::BottomLeft));
-                }
-            }
-            BoardLocation::BottomCentre => {
-                if self.bottom_centre.is_none() {
-                    self.bottom_centre = Some(player);
-                } else {
-                    return Err(BoardError::BoardLocationOccupied(
-                        BoardLocation::BottomCentre,
-                    ));
-                }
-            }
-            BoardLocation::BottomRight => {
-                if self.bottom_right.is_none() {
-                    self.bottom_right = Some(player);
-                } else {
-                    return Err(BoardError::BoardLocationOccupied(
-                        BoardLocation::BottomRight,
-                    ));
-                }
-            }
+        if self.token_count(&player) > self.token_count(&player.opponent()) {
+            return Err(BoardError::OutOfTurn(player));
         }
-        Ok(())
+        self.place(location, player)
+    }
+
+    // Like `play`, but without the turn check, for setting up positions and
+    // for games where the same token may be played twice running.
+    pub(crate) fn place(
+        &mut self,
+        location: BoardLocation,
+        player: BoardToken,
+    ) -> Result<(), BoardError> {
+        self.grid
+            .play(location.cell(), player)
+            .map_err(|_| BoardError::BoardLocationOccupied(location))
     }
 
     pub fn get(&self, location: BoardLocation) -> Option<BoardToken> {
-        match location {
-            BoardLocation::TopLeft => self.top_left.clone(),
-            BoardLocation::TopCentre => self.top_centre.clone(),
-            BoardLocation::TopRight => self.top_right.clone(),
-            BoardLocation::MiddleLeft => self.middle_left.clone(),
-            BoardLocation::MiddleCentre => self.middle_centre.clone(),
-            BoardLocation::MiddleRight => self.middle_right.clone(),
-            BoardLocation::BottomLeft => self.bottom_left.clone(),
-            BoardLocation::BottomCentre => self.bottom_centre.clone(),
-            BoardLocation::BottomRight => self.bottom_right.clone(),
+        self.grid.get(location.cell())
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = (BoardLocation, Option<BoardToken>)> + '_ {
+        BoardLocation::ALL
+            .into_iter()
+            .map(|location| (location.clone(), self.get(location)))
+    }
+
+    pub fn empty_locations(&self) -> Vec<BoardLocation> {
+        self.cells()
+            .filter(|(_, token)| token.is_none())
+            .map(|(location, _)| location)
+            .collect()
+    }
+
+    pub fn legal_moves(&self) -> Vec<BoardLocation> {
+        if self.winner().is_some() {
+            Vec::new()
+        } else {
+            self.empty_locations()
         }
     }
 
+    pub fn token_count(&self, token: &BoardToken) -> usize {
+        self.cells()
+            .filter(|(_, cell)| cell.as_ref() == Some(token))
+            .count()
+    }
+
+    // Works out whose turn it is from the token counts, given which token
+    // opened the game. Returns `None` once the game is over or if the counts
+    // could not have come from alternating play.
+    pub fn side_to_move(&self, first: &BoardToken) -> Option<BoardToken> {
+        if self.winner().is_some() || self.is_full() {
+            return None;
+        }
+        let first_count = self.token_count(first);
+        let second_count = self.token_count(&first.opponent());
+        if first_count == second_count {
+            Some(first.clone())
+        } else if first_count == second_count + 1 {
+            Some(first.opponent())
+        } else {
+            None
+        }
+    }
+
+    pub fn is_line_win(&self, line: &BoardLine) -> bool {
+        let [first, second, third] = line.contents(|location| self.get(location));
+        first.is_some() && first == second && first == third
+    }
+
+    pub fn winning_lines(&self) -> Vec<BoardLine> {
+        BoardLine::ALL
+            .into_iter()
+            .filter(|line| self.is_line_win(line))
+            .collect()
+    }
+
+    pub fn winner(&self) -> Option<(BoardToken, BoardLine)> {
+        let line = self.winning_lines().into_iter().next()?;
+        let [location, _, _] = line.locations();
+        self.get(location).map(|token| (token, line))
+    }
+
     pub fn is_full(&self) -> bool {
-        self.top_left.is_some()
-            && self.top_centre.is_some()
-            && self.top_right.is_some()
-            && self.middle_left.is_some()
-            && self.middle_centre.is_some()
-            && self.middle_right.is_some()
-            && self.bottom_left.is_some()
-            && self.bottom_centre.is_some()
-            && self.bottom_right.is_some()
+        self.grid.is_full()
     }
+
     pub fn is_top_row_win(&self) -> bool {
-        self.top_left.is_some()
-            && self.top_left == self.top_centre
-            && self.top_left == self.top_right
+        self.is_line_win(&BoardLine::TopRow)
     }
 
     pub fn is_middle_row_win(&self) -> bool {
-        self.middle_left.is_some()
-            && self.middle_left == self.middle_centre
-            && self.middle_left == self.middle_right
+        self.is_line_win(&BoardLine::MiddleRow)
     }
 
     pub fn is_bottom_row_win(&self) -> bool {
-        self.bottom_left.is_some()
-            && self.bottom_left == self.bottom_centre
-            && self.bottom_left == self.bottom_right
+        self.is_line_win(&BoardLine::BottomRow)
     }
 
     pub fn is_left_column_win(&self) -> bool {
-        self.top_left.is_some()
-            && self.top_left == self.middle_left
-            && self.top_left == self.bottom_left
+        self.is_line_win(&BoardLine::LeftColumn)
     }
 
     pub fn is_centre_column_win(&self) -> bool {
-        self.top_centre.is_some()
-            && self.top_centre == self.middle_centre
-            && self.top_centre == self.bottom_centre
+        self.is_line_win(&BoardLine::CentreColumn)
     }
 
     pub fn is_right_column_win(&self) -> bool {
-        self.top_right.is_some()
-            && self.top_right == self.middle_right
-            && self.top_right == self.bottom_right
+        self.is_line_win(&BoardLine::RightColumn)
     }
 
     pub fn is_left_right_diagonal_win(&self) -> bool {
-        self.top_left.is_some()
-            && self.top_left == self.middle_centre
-            && self.top_left == self.bottom_right
+        self.is_line_win(&BoardLine::LeftRightDiagonal)
     }
 
     pub fn is_right_left_diagonal_win(&self) -> bool {
-        self.top_right.is_some()
-            && self.top_right == self.middle_centre
-            && self.top_right == self.bottom_left
+        self.is_line_win(&BoardLine::RightLeftDiagonal)
     }
 }
 
@@ -199,6 +343,96 @@ fn display_token(token: &Option<BoardToken>) -> String {
     }
 }
 
+impl Board for GameBoard {
+    type Token = BoardToken;
+    type Move = BoardLocation;
+    type Line = BoardLine;
+    type Error = BoardError;
+
+    fn legal_moves(&self, _token: &BoardToken) -> Vec<BoardLocation> {
+        GameBoard::legal_moves(self)
+    }
+
+    // The game keeps the turns itself, and a player who skips their turn
+    // lets the other play twice running.
+    fn play_move(
+        &mut self,
+        token: &BoardToken,
+        location: &BoardLocation,
+    ) -> Result<(), BoardError> {
+        self.place(location.clone(), token.clone())
+    }
+
+    fn won_by(&self, rules: RuleSet) -> Option<(BoardToken, Vec<BoardLine>)> {
+        let (completed_by, _) = self.winner()?;
+        Some((rules.winner(&completed_by), self.winning_lines()))
+    }
+
+    fn is_finished(&self) -> bool {
+        self.is_full()
+    }
+
+    fn move_prompt(&self, _token: &BoardToken) -> String {
+        "Enter a location (1-9)".into()
+    }
+
+    fn weight_index(&self, location: &BoardLocation) -> Option<usize> {
+        Some(location.index())
+    }
+}
+
+impl fmt::Display for BoardLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BoardLocation::TopLeft => "top left",
+            BoardLocation::TopCentre => "top centre",
+            BoardLocation::TopRight => "top right",
+            BoardLocation::MiddleLeft => "middle left",
+            BoardLocation::MiddleCentre => "middle centre",
+            BoardLocation::MiddleRight => "middle right",
+            BoardLocation::BottomLeft => "bottom left",
+            BoardLocation::BottomCentre => "bottom centre",
+            BoardLocation::BottomRight => "bottom right",
+        };
+        write!(f, "{} ({})", name, self.number())
+    }
+}
+
+// Parsed from the location's number, 1 to 9.
+impl FromStr for BoardLocation {
+    type Err = PlayerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim()
+            .parse()
+            .ok()
+            .and_then(BoardLocation::from_number)
+            .ok_or(PlayerError::InvalidLocation)
+    }
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardError::BoardLocationOccupied(location) => {
+                write!(f, "{} is already occupied", location)
+            }
+            BoardError::OutOfTurn(token) => write!(f, "{} played out of turn", token),
+        }
+    }
+}
+
+impl std::error::Error for BoardError {}
+
+impl fmt::Display for BoardToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardToken::Cross => write!(f, "X"),
+            BoardToken::Nought => write!(f, "O"),
+        }
+    }
+}
+
 impl fmt::Display for GameBoard {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(
@@ -215,15 +449,15 @@ impl fmt::Display for GameBoard {
   {}  |  {}  |  {}  
 7    |8    |9    
 "#,
-            display_token(&self.top_left),
-            display_token(&self.top_centre),
-            display_token(&self.top_right),
-            display_token(&self.middle_left),
-            display_token(&self.middle_centre),
-            display_token(&self.middle_right),
-            display_token(&self.bottom_left),
-            display_token(&self.bottom_centre),
-            display_token(&self.bottom_right)
+            display_token(&self.get(BoardLocation::TopLeft)),
+            display_token(&self.get(BoardLocation::TopCentre)),
+            display_token(&self.get(BoardLocation::TopRight)),
+            display_token(&self.get(BoardLocation::MiddleLeft)),
+            display_token(&self.get(BoardLocation::MiddleCentre)),
+            display_token(&self.get(BoardLocation::MiddleRight)),
+            display_token(&self.get(BoardLocation::BottomLeft)),
+            display_token(&self.get(BoardLocation::BottomCentre)),
+            display_token(&self.get(BoardLocation::BottomRight))
         )
     }
 }
@@ -236,11 +470,17 @@ mod tests {
     pub fn test_is_top_row_win() {
         let mut board = GameBoard::default();
         assert!(!board.is_top_row_win());
-        board.play(BoardLocation::TopCentre, BoardToken::Cross);
+        board
+            .place(BoardLocation::TopCentre, BoardToken::Cross)
+            .unwrap();
         assert!(!board.is_top_row_win());
-        board.play(BoardLocation::TopRight, BoardToken::Cross);
+        board
+            .place(BoardLocation::TopRight, BoardToken::Cross)
+            .unwrap();
         assert!(!board.is_top_row_win());
-        board.play(BoardLocation::TopLeft, BoardToken::Cross);
+        board
+            .place(BoardLocation::TopLeft, BoardToken::Cross)
+            .unwrap();
         assert!(board.is_top_row_win());
     }
 
@@ -248,11 +488,17 @@ mod tests {
     pub fn test_is_middle_row_win() {
         let mut board = GameBoard::default();
         assert!(!board.is_middle_row_win());
-        board.play(BoardLocation::MiddleCentre, BoardToken::Cross);
+        board
+            .place(BoardLocation::MiddleCentre, BoardToken::Cross)
+            .unwrap();
         assert!(!board.is_middle_row_win());
-        board.play(BoardLocation::MiddleRight, BoardToken::Cross);
+        board
+            .place(BoardLocation::MiddleRight, BoardToken::Cross)
+            .unwrap();
         assert!(!board.is_middle_row_win());
-        board.play(BoardLocation::MiddleLeft, BoardToken::Cross);
+        board
+            .place(BoardLocation::MiddleLeft, BoardToken::Cross)
+            .unwrap();
         assert!(board.is_middle_row_win());
     }
 
@@ -260,11 +506,17 @@ mod tests {
     pub fn test_is_bottom_row_win() {
         let mut board = GameBoard::default();
         assert!(!board.is_bottom_row_win());
-        board.play(BoardLocation::BottomCentre, BoardToken::Cross);
+        board
+            .place(BoardLocation::BottomCentre, BoardToken::Cross)
+            .unwrap();
         assert!(!board.is_bottom_row_win());
-        board.play(BoardLocation::BottomRight, BoardToken::Cross);
+        board
+            .place(BoardLocation::BottomRight, BoardToken::Cross)
+            .unwrap();
         assert!(!board.is_bottom_row_win());
-        board.play(BoardLocation::BottomLeft, BoardToken::Cross);
+        board
+            .place(BoardLocation::BottomLeft, BoardToken::Cross)
+            .unwrap();
         assert!(board.is_bottom_row_win());
     }
 
@@ -272,11 +524,17 @@ mod tests {
     pub fn test_is_left_column_win() {
         let mut board = GameBoard::default();
         assert!(!board.is_left_column_win());
-        board.play(BoardLocation::TopLeft, BoardToken::Cross);
+        board
+            .place(BoardLocation::TopLeft, BoardToken::Cross)
+            .unwrap();
         assert!(!board.is_left_column_win());
-        board.play(BoardLocation::MiddleLeft, BoardToken::Cross);
+        board
+            .place(BoardLocation::MiddleLeft, BoardToken::Cross)
+            .unwrap();
         assert!(!board.is_left_column_win());
-        board.play(BoardLocation::BottomLeft, BoardToken::Cross);
+        board
+            .place(BoardLocation::BottomLeft, BoardToken::Cross)
+            .unwrap();
         assert!(board.is_left_column_win());
     }
 
@@ -284,11 +542,17 @@ mod tests {
     pub fn test_is_centre_column_win() {
         let mut board = GameBoard::default();
         assert!(!board.is_centre_column_win());
-        board.play(BoardLocation::TopCentre, BoardToken::Cross);
+        board
+            .place(BoardLocation::TopCentre, BoardToken::Cross)
+            .unwrap();
         assert!(!board.is_centre_column_win());
-        board.play(BoardLocation::MiddleCentre, BoardToken::Cross);
+        board
+            .place(BoardLocation::MiddleCentre, BoardToken::Cross)
+            .unwrap();
         assert!(!board.is_centre_column_win());
-        board.play(BoardLocation::BottomCentre, BoardToken::Cross);
+        board
+            .place(BoardLocation::BottomCentre, BoardToken::Cross)
+            .unwrap();
         assert!(board.is_centre_column_win());
     }
 
@@ -296,11 +560,17 @@ mod tests {
     pub fn test_is_right_column_win() {
         let mut board = GameBoard::default();
         assert!(!board.is_right_column_win());
-        board.play(BoardLocation::TopRight, BoardToken::Cross);
+        board
+            .place(BoardLocation::TopRight, BoardToken::Cross)
+            .unwrap();
         assert!(!board.is_right_column_win());
-        board.play(BoardLocation::MiddleRight, BoardToken::Cross);
+        board
+            .place(BoardLocation::MiddleRight, BoardToken::Cross)
+            .unwrap();
         assert!(!board.is_right_column_win());
-        board.play(BoardLocation::BottomRight, BoardToken::Cross);
+        board
+            .place(BoardLocation::BottomRight, BoardToken::Cross)
+            .unwrap();
         assert!(board.is_right_column_win());
     }
 
@@ -308,11 +578,17 @@ mod tests {
     pub fn test_is_left_right_diagonal_win() {
         let mut board = GameBoard::default();
         assert!(!board.is_left_right_diagonal_win());
-        board.play(BoardLocation::TopLeft, BoardToken::Cross);
+        board
+            .place(BoardLocation::TopLeft, BoardToken::Cross)
+            .unwrap();
         assert!(!board.is_left_right_diagonal_win());
-        board.play(BoardLocation::MiddleCentre, BoardToken::Cross);
+        board
+            .place(BoardLocation::MiddleCentre, BoardToken::Cross)
+            .unwrap();
         assert!(!board.is_left_right_diagonal_win());
-        board.play(BoardLocation::BottomRight, BoardToken::Cross);
+        board
+            .place(BoardLocation::BottomRight, BoardToken::Cross)
+            .unwrap();
         assert!(board.is_left_right_diagonal_win());
     }
 
@@ -320,11 +596,17 @@ mod tests {
     pub fn test_is_right_left_diagonal_win() {
         let mut board = GameBoard::default();
         assert!(!board.is_right_left_diagonal_win());
-        board.play(BoardLocation::TopRight, BoardToken::Cross);
+        board
+            .place(BoardLocation::TopRight, BoardToken::Cross)
+            .unwrap();
         assert!(!board.is_right_left_diagonal_win());
-        board.play(BoardLocation::MiddleCentre, BoardToken::Cross);
+        board
+            .place(BoardLocation::MiddleCentre, BoardToken::Cross)
+            .unwrap();
         assert!(!board.is_right_left_diagonal_win());
-        board.play(BoardLocation::BottomLeft, BoardToken::Cross);
+        board
+            .place(BoardLocation::BottomLeft, BoardToken::Cross)
+            .unwrap();
         assert!(board.is_right_left_diagonal_win());
     }
 
@@ -333,15 +615,18 @@ mod tests {
         let mut board = GameBoard::default();
         let res = board.play(BoardLocation::MiddleCentre, BoardToken::Cross);
         assert!(res.is_ok());
-        assert_eq!(board.middle_centre, Some(BoardToken::Cross));
-        assert!(board.top_left.is_none());
-        assert!(board.top_centre.is_none());
-        assert!(board.top_right.is_none());
-        assert!(board.middle_left.is_none());
-        assert!(board.middle_right.is_none());
-        assert!(board.bottom_left.is_none());
-        assert!(board.bottom_centre.is_none());
-        assert!(board.bottom_right.is_none());
+        assert_eq!(
+            board.get(BoardLocation::MiddleCentre),
+            Some(BoardToken::Cross)
+        );
+        assert!(board.get(BoardLocation::TopLeft).is_none());
+        assert!(board.get(BoardLocation::TopCentre).is_none());
+        assert!(board.get(BoardLocation::TopRight).is_none());
+        assert!(board.get(BoardLocation::MiddleLeft).is_none());
+        assert!(board.get(BoardLocation::MiddleRight).is_none());
+        assert!(board.get(BoardLocation::BottomLeft).is_none());
+        assert!(board.get(BoardLocation::BottomCentre).is_none());
+        assert!(board.get(BoardLocation::BottomRight).is_none());
     }
 
     #[test]
@@ -354,4 +639,130 @@ mod tests {
         let res = board.play(BoardLocation::MiddleCentre, BoardToken::Nought);
         assert!(res.is_err());
     }
+
+    #[test]
+    pub fn test_legal_moves() {
+        let mut board = GameBoard::default();
+        assert_eq!(9, board.legal_moves().len());
+        board
+            .play(BoardLocation::MiddleCentre, BoardToken::Cross)
+            .unwrap();
+        let moves = board.legal_moves();
+        assert_eq!(8, moves.len());
+        assert!(!moves.contains(&BoardLocation::MiddleCentre));
+    }
+
+    #[test]
+    pub fn test_no_legal_moves_after_win() {
+        let mut board = GameBoard::default();
+        board
+            .place(BoardLocation::TopLeft, BoardToken::Cross)
+            .unwrap();
+        board
+            .place(BoardLocation::TopCentre, BoardToken::Cross)
+            .unwrap();
+        board
+            .place(BoardLocation::TopRight, BoardToken::Cross)
+            .unwrap();
+        assert!(board.legal_moves().is_empty());
+        assert_eq!(6, board.empty_locations().len());
+    }
+
+    #[test]
+    pub fn test_cells() {
+        let mut board = GameBoard::default();
+        board
+            .play(BoardLocation::BottomRight, BoardToken::Nought)
+            .unwrap();
+        let cells: Vec<(BoardLocation, Option<BoardToken>)> = board.cells().collect();
+        assert_eq!(9, cells.len());
+        assert_eq!((BoardLocation::TopLeft, None), cells[0]);
+        assert_eq!(
+            (BoardLocation::BottomRight, Some(BoardToken::Nought)),
+            cells[8]
+        );
+    }
+
+    #[test]
+    pub fn test_token_count_and_side_to_move() {
+        let mut board = GameBoard::default();
+        assert_eq!(
+            Some(BoardToken::Cross),
+            board.side_to_move(&BoardToken::Cross)
+        );
+        board
+            .play(BoardLocation::MiddleCentre, BoardToken::Cross)
+            .unwrap();
+        assert_eq!(1, board.token_count(&BoardToken::Cross));
+        assert_eq!(0, board.token_count(&BoardToken::Nought));
+        assert_eq!(
+            Some(BoardToken::Nought),
+            board.side_to_move(&BoardToken::Cross)
+        );
+        assert_eq!(None, board.side_to_move(&BoardToken::Nought));
+    }
+
+    #[test]
+    pub fn test_winner() {
+        let mut board = GameBoard::default();
+        assert_eq!(None, board.winner());
+        board
+            .place(BoardLocation::TopRight, BoardToken::Nought)
+            .unwrap();
+        board
+            .place(BoardLocation::MiddleCentre, BoardToken::Nought)
+            .unwrap();
+        board
+            .place(BoardLocation::BottomLeft, BoardToken::Nought)
+            .unwrap();
+        assert_eq!(
+            Some((BoardToken::Nought, BoardLine::RightLeftDiagonal)),
+            board.winner()
+        );
+        assert_eq!(None, board.side_to_move(&BoardToken::Cross));
+    }
+
+    #[test]
+    pub fn test_occupied_error() {
+        let mut board = GameBoard::default();
+        board
+            .play(BoardLocation::TopLeft, BoardToken::Cross)
+            .unwrap();
+        let err = board
+            .play(BoardLocation::TopLeft, BoardToken::Nought)
+            .unwrap_err();
+        assert_eq!("top left (1) is already occupied", err.to_string());
+    }
+
+    #[test]
+    pub fn test_two_crosses_in_a_row() {
+        let mut board = GameBoard::default();
+        board
+            .play(BoardLocation::TopLeft, BoardToken::Cross)
+            .unwrap();
+        let err = board
+            .play(BoardLocation::TopCentre, BoardToken::Cross)
+            .unwrap_err();
+        assert!(matches!(err, BoardError::OutOfTurn(BoardToken::Cross)));
+        assert_eq!("X played out of turn", err.to_string());
+        assert_eq!(None, board.get(BoardLocation::TopCentre));
+        board
+            .play(BoardLocation::TopCentre, BoardToken::Nought)
+            .unwrap();
+        board
+            .play(BoardLocation::TopRight, BoardToken::Cross)
+            .unwrap();
+    }
+
+    #[test]
+    pub fn test_either_token_opens() {
+        let mut board = GameBoard::default();
+        board
+            .play(BoardLocation::MiddleCentre, BoardToken::Nought)
+            .unwrap();
+        assert!(matches!(
+            board.play(BoardLocation::TopLeft, BoardToken::Nought),
+            Err(BoardError::OutOfTurn(BoardToken::Nought))
+        ));
+    }
 }
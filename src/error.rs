@@ -0,0 +1,136 @@
+use crate::board::BoardError;
+use crate::grid::GridError;
+use crate::notation::NotationError;
+use crate::player::PlayerError;
+use crate::record::RecordError;
+use crate::GameError;
+use std::fmt;
+use std::io;
+
+// Any error the library can produce, so callers can use `?` across modules.
+#[derive(Debug)]
+pub enum Error {
+    Board(BoardError),
+    Grid(GridError),
+    Player(PlayerError),
+    Io(io::Error),
+    Notation(NotationError),
+    Record(RecordError),
+    Game(GameError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Board(err) => write!(f, "board error: {}", err),
+            Error::Grid(err) => write!(f, "board error: {}", err),
+            Error::Player(err) => write!(f, "player error: {}", err),
+            Error::Io(err) => write!(f, "I/O error: {}", err),
+            Error::Notation(err) => write!(f, "bad position: {}", err),
+            Error::Record(err) => write!(f, "bad game record: {}", err),
+            Error::Game(err) => write!(f, "game error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Board(err) => Some(err),
+            Error::Grid(err) => Some(err),
+            Error::Player(err) => Some(err),
+            Error::Io(err) => Some(err),
+            Error::Notation(err) => Some(err),
+            Error::Record(err) => Some(err),
+            Error::Game(err) => Some(err),
+        }
+    }
+}
+
+impl From<BoardError> for Error {
+    fn from(err: BoardError) -> Self {
+        Error::Board(err)
+    }
+}
+
+impl From<GridError> for Error {
+    fn from(err: GridError) -> Self {
+        Error::Grid(err)
+    }
+}
+
+impl From<PlayerError> for Error {
+    fn from(err: PlayerError) -> Self {
+        Error::Player(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<NotationError> for Error {
+    fn from(err: NotationError) -> Self {
+        Error::Notation(err)
+    }
+}
+
+// A record that couldn't be read or written is an I/O problem, not a bad
+// record.
+impl From<RecordError> for Error {
+    fn from(err: RecordError) -> Self {
+        match err {
+            RecordError::Io(err) => Error::Io(err),
+            err => Error::Record(err),
+        }
+    }
+}
+
+impl From<GameError> for Error {
+    fn from(err: GameError) -> Self {
+        Error::Game(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::*;
+    use crate::notation::Position;
+
+    fn play_on(position: &str, location: BoardLocation) -> Result<GameBoard, Error> {
+        let position: Position = position.parse()?;
+        let mut board = position.board;
+        board.play(location, position.to_move)?;
+        Ok(board)
+    }
+
+    #[test]
+    pub fn test_errors_compose() {
+        assert!(play_on(".../.X./... o", BoardLocation::TopLeft).is_ok());
+        let err = play_on(".../.X./... o", BoardLocation::MiddleCentre).unwrap_err();
+        assert_eq!(
+            "board error: middle centre (5) is already occupied",
+            err.to_string()
+        );
+        let err = play_on(".../.Z./... o", BoardLocation::TopLeft).unwrap_err();
+        assert_eq!("bad position: 'Z' is not one of X, O or .", err.to_string());
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    pub fn test_record_io_error() {
+        let err: Error = RecordError::Io(io::Error::new(io::ErrorKind::NotFound, "gone")).into();
+        assert!(matches!(err, Error::Io(_)));
+        assert_eq!("I/O error: gone", err.to_string());
+        assert!(std::error::Error::source(&err).is_some());
+
+        let err: Error = RecordError::MissingField("moves").into();
+        assert_eq!(
+            "bad game record: the moves field is missing",
+            err.to_string()
+        );
+    }
+}
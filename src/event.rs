@@ -0,0 +1,49 @@
+use crate::board::*;
+use crate::record::RecordedPlayer;
+use crate::{GameResult, Move, Turn};
+
+// Something that happened during a game. Each event carries copies of the
+// state it describes so observers can keep it as long as they like.
+#[derive(Debug, PartialEq, Clone)]
+pub enum GameEvent<B: Board = GameBoard> {
+    GameStarted {
+        player1: RecordedPlayer<B::Token>,
+        player2: RecordedPlayer<B::Token>,
+        board: B,
+    },
+    TurnStarted {
+        turn: Turn,
+        board: B,
+    },
+    MovePlayed {
+        played: Move<B>,
+        board: B,
+    },
+    IllegalMove {
+        turn: Turn,
+        location: B::Move,
+    },
+    // The player had no move to give.
+    OutOfMoves {
+        turn: Turn,
+    },
+    TurnSkipped {
+        turn: Turn,
+    },
+    Forfeit {
+        turn: Turn,
+    },
+    GameOver(GameResult<B>),
+}
+
+// Receives every event of a game it has been added to, in the order they
+// happen. Closures taking a `&GameEvent` are observers too.
+pub trait Observer<B: Board = GameBoard> {
+    fn notify(&mut self, event: &GameEvent<B>);
+}
+
+impl<B: Board, F: FnMut(&GameEvent<B>)> Observer<B> for F {
+    fn notify(&mut self, event: &GameEvent<B>) {
+        self(event)
+    }
+}
@@ -0,0 +1,282 @@
+use crate::board::BoardToken;
+use crate::grid::*;
+use crate::player::{Player, PlayerError};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+// Freestyle is won by five or more in a row. Standard is won by exactly
+// five; a line of six or more doesn't count.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GomokuRules {
+    Freestyle,
+    Standard,
+}
+
+impl GomokuRules {
+    pub const SIZE: usize = 15;
+    pub const WIN_LENGTH: usize = 5;
+
+    pub fn grid(&self) -> Grid {
+        let rule = match self {
+            GomokuRules::Freestyle => WinRule::AtLeast,
+            GomokuRules::Standard => WinRule::Exactly,
+        };
+        Grid::new_with_rule(Self::SIZE, Self::SIZE, Self::WIN_LENGTH, rule)
+            .expect("the gomoku board is a valid grid")
+    }
+}
+
+// Scores each free cell by the lines it would make for itself and the lines
+// it would break up for its opponent, and plays the best. Searching ahead is
+// out of the question on a board this size. Works for any grid, not just
+// gomoku.
+pub struct GomokuAi {
+    pub id: String,
+    pub token: BoardToken,
+    rng: StdRng,
+}
+
+// How far from the nearest token a move is worth considering.
+const REACH: usize = 2;
+const WIN: u64 = 1_000_000;
+
+impl GomokuAi {
+    pub fn new(id: &str, token: BoardToken) -> Self {
+        Self {
+            id: String::from(id),
+            token,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    // Makes the choice between equally good cells repeatable.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    // Attacking counts for a little more than defending, so that the player
+    // takes a win rather than blocking one.
+    pub fn score(grid: &Grid, cell: Cell, token: &BoardToken) -> u64 {
+        let attack = line_scores(grid, cell, token);
+        let defence = line_scores(grid, cell, &token.opponent());
+        attack * 10 + defence * 9
+    }
+}
+
+// The value of the lines through `cell` if `token` were played there.
+fn line_scores(grid: &Grid, cell: Cell, token: &BoardToken) -> u64 {
+    [(0, 1), (1, 0), (1, 1), (1, -1)]
+        .into_iter()
+        .map(|(rows, columns)| {
+            let (ahead, open_ahead) = extent(grid, cell, (rows, columns), token);
+            let (behind, open_behind) = extent(grid, cell, (-rows, -columns), token);
+            line_value(
+                grid,
+                ahead + behind + 1,
+                open_ahead as usize + open_behind as usize,
+            )
+        })
+        .sum()
+}
+
+// How many of `token` follow `cell` in a direction, and whether the line is
+// then left open by an empty cell.
+fn extent(grid: &Grid, cell: Cell, direction: (isize, isize), token: &BoardToken) -> (usize, bool) {
+    let mut count = 0;
+    let mut next = cell;
+    loop {
+        let Some(step) = next.step(direction).filter(|step| grid.contains(*step)) else {
+            return (count, false);
+        };
+        match grid.get(step) {
+            Some(found) if &found == token => {
+                count += 1;
+                next = step;
+            }
+            Some(_) => return (count, false),
+            None => return (count, true),
+        }
+    }
+}
+
+fn line_value(grid: &Grid, length: usize, open_ends: usize) -> u64 {
+    let win_length = grid.win_length();
+    if length >= win_length {
+        return if grid.is_winning_length(length) {
+            WIN
+        } else {
+            0
+        };
+    }
+    match (win_length - length, open_ends) {
+        (_, 0) => 0,
+        // Can't be stopped.
+        (1, 2) => WIN / 10,
+        // Has to be stopped at once, or must be answered next turn.
+        (1, _) => WIN / 50,
+        (2, 2) => WIN / 100,
+        // Kept below the tiers above however long the winning line is.
+        (missing, open_ends) => {
+            10u64
+                .saturating_pow((win_length - missing) as u32)
+                .min(WIN / 1000)
+                * open_ends as u64
+        }
+    }
+}
+
+impl Player<Grid> for GomokuAi {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn token(&self) -> BoardToken {
+        self.token.clone()
+    }
+
+    fn play(&mut self, grid: &Grid) -> Result<(BoardToken, Cell), PlayerError> {
+        let empty = grid.empty_cells();
+        if empty.is_empty() {
+            return Err(PlayerError::NoMoreMoves);
+        }
+        if empty.len() == grid.width() * grid.height() {
+            let centre = Cell::new(grid.height() / 2, grid.width() / 2);
+            return Ok((self.token.clone(), centre));
+        }
+        let near: Vec<Cell> = empty
+            .iter()
+            .copied()
+            .filter(|cell| is_near_a_token(grid, *cell))
+            .collect();
+        let candidates = if near.is_empty() { empty } else { near };
+        let scored: Vec<(u64, Cell)> = candidates
+            .into_iter()
+            .map(|cell| (Self::score(grid, cell, &self.token), cell))
+            .collect();
+        let best = scored.iter().map(|(score, _)| *score).max().unwrap();
+        let best_cells: Vec<Cell> = scored
+            .into_iter()
+            .filter(|(score, _)| *score == best)
+            .map(|(_, cell)| cell)
+            .collect();
+        Ok((
+            self.token.clone(),
+            *best_cells.choose(&mut self.rng).unwrap(),
+        ))
+    }
+}
+
+fn is_near_a_token(grid: &Grid, cell: Cell) -> bool {
+    let rows = cell.row.saturating_sub(REACH)..=(cell.row + REACH).min(grid.height() - 1);
+    rows.into_iter().any(|row| {
+        let columns =
+            cell.column.saturating_sub(REACH)..=(cell.column + REACH).min(grid.width() - 1);
+        columns
+            .into_iter()
+            .any(|column| grid.get(Cell::new(row, column)).is_some())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::RandomPlayer;
+    use crate::{Game, GameResult};
+
+    fn gomoku(rules: GomokuRules, crosses: &[&str], noughts: &[&str]) -> Grid {
+        let mut grid = rules.grid();
+        for (names, token) in [(crosses, BoardToken::Cross), (noughts, BoardToken::Nought)] {
+            for name in names {
+                grid.play(name.parse().unwrap(), token.clone()).unwrap();
+            }
+        }
+        grid
+    }
+
+    fn ai(token: BoardToken) -> GomokuAi {
+        let mut ai = GomokuAi::new("ai", token);
+        ai.set_seed(1);
+        ai
+    }
+
+    fn choice(token: BoardToken, grid: &Grid) -> Cell {
+        let (_, cell) = ai(token).play(grid).unwrap();
+        cell
+    }
+
+    #[test]
+    pub fn test_opens_in_the_centre() {
+        let grid = GomokuRules::Freestyle.grid();
+        assert_eq!(
+            "H8".parse::<Cell>().unwrap(),
+            choice(BoardToken::Cross, &grid)
+        );
+    }
+
+    #[test]
+    pub fn test_takes_a_win_before_blocking() {
+        let grid = gomoku(
+            GomokuRules::Freestyle,
+            &["D4", "E4", "F4", "G4"],
+            &["D10", "E10", "F10", "G10", "A1"],
+        );
+        let cell = choice(BoardToken::Nought, &grid);
+        assert!(cell == "C10".parse().unwrap() || cell == "H10".parse().unwrap());
+    }
+
+    #[test]
+    pub fn test_blocks_four() {
+        let grid = gomoku(
+            GomokuRules::Freestyle,
+            &["D4", "E4", "F4", "G4"],
+            &["C4", "A10", "B12"],
+        );
+        assert_eq!(
+            "H4".parse::<Cell>().unwrap(),
+            choice(BoardToken::Nought, &grid)
+        );
+    }
+
+    #[test]
+    pub fn test_long_lines() {
+        // Nineteen crosses in a row, seven short of the winning length.
+        let mut grid = Grid::new(26, 26, 26).unwrap();
+        for column in 0..19 {
+            grid.play(Cell::new(0, column), BoardToken::Cross).unwrap();
+        }
+        let extend = GomokuAi::score(&grid, Cell::new(0, 19), &BoardToken::Cross);
+        let elsewhere = GomokuAi::score(&grid, Cell::new(5, 5), &BoardToken::Cross);
+        assert!(extend > elsewhere);
+        assert!(extend < WIN);
+        assert_eq!(Cell::new(0, 19), choice(BoardToken::Cross, &grid));
+    }
+
+    #[test]
+    pub fn test_standard_rules_ignore_overlines() {
+        // Filling F8 would make six in a row, which only wins at freestyle.
+        let crosses = ["A8", "B8", "C8", "D8", "E8", "G8"];
+        let freestyle = gomoku(GomokuRules::Freestyle, &crosses, &[]);
+        let standard = gomoku(GomokuRules::Standard, &crosses, &[]);
+        let f8 = "F8".parse().unwrap();
+        assert!(GomokuAi::score(&freestyle, f8, &BoardToken::Cross) >= WIN * 10);
+        assert!(GomokuAi::score(&standard, f8, &BoardToken::Cross) < WIN);
+
+        let mut grid = standard.clone();
+        grid.play(f8, BoardToken::Cross).unwrap();
+        assert_eq!(None, grid.winner());
+    }
+
+    #[test]
+    pub fn test_beats_a_random_player() {
+        for seed in 0..5 {
+            let mut game = Game::new_with_board(GomokuRules::Standard.grid());
+            game.set_verbose(false);
+            let mut random = RandomPlayer::new("random", BoardToken::Cross);
+            random.set_seed(seed);
+            game.register_player(random).unwrap();
+            game.register_player(ai(BoardToken::Nought)).unwrap();
+            assert!(matches!(game.play().unwrap(), GameResult::Player2(_)));
+        }
+    }
+}
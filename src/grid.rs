@@ -0,0 +1,545 @@
+use crate::board::{Board, BoardToken};
+use crate::rules::RuleSet;
+use std::fmt;
+use std::str::FromStr;
+
+// A square on a grid, counted from zero at the top left. Shown and parsed as
+// a column letter followed by a row number, so the top left is A1.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Cell {
+    pub row: usize,
+    pub column: usize,
+}
+
+// A line of at least the winning length in a single token.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Run {
+    pub token: BoardToken,
+    pub cells: Vec<Cell>,
+}
+
+// Whether a line longer than the winning length still wins.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum WinRule {
+    AtLeast,
+    Exactly,
+}
+
+// A board of any size up to 26 by 26, won by getting `win_length` tokens in a
+// row across, down or diagonally.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Grid {
+    width: usize,
+    height: usize,
+    win_length: usize,
+    win_rule: WinRule,
+    cells: Vec<Option<BoardToken>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GridError {
+    BadSize {
+        width: usize,
+        height: usize,
+        win_length: usize,
+    },
+    OutOfBounds(Cell),
+    Occupied(Cell),
+    BadCell(String),
+}
+
+// Right, down, down and right, down and left.
+const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+impl Cell {
+    pub fn new(row: usize, column: usize) -> Self {
+        Self { row, column }
+    }
+
+    pub(crate) fn step(&self, (rows, columns): (isize, isize)) -> Option<Cell> {
+        Some(Cell {
+            row: self.row.checked_add_signed(rows)?,
+            column: self.column.checked_add_signed(columns)?,
+        })
+    }
+}
+
+impl Grid {
+    pub const MAX_SIZE: usize = 26;
+
+    pub fn new(width: usize, height: usize, win_length: usize) -> Result<Self, GridError> {
+        Self::new_with_rule(width, height, win_length, WinRule::AtLeast)
+    }
+
+    pub fn new_with_rule(
+        width: usize,
+        height: usize,
+        win_length: usize,
+        win_rule: WinRule,
+    ) -> Result<Self, GridError> {
+        if width == 0
+            || height == 0
+            || width > Self::MAX_SIZE
+            || height > Self::MAX_SIZE
+            || win_length == 0
+            || win_length > width.max(height)
+        {
+            return Err(GridError::BadSize {
+                width,
+                height,
+                win_length,
+            });
+        }
+        Ok(Self {
+            width,
+            height,
+            win_length,
+            win_rule,
+            cells: vec![None; width * height],
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn win_length(&self) -> usize {
+        self.win_length
+    }
+
+    pub fn win_rule(&self) -> WinRule {
+        self.win_rule
+    }
+
+    // Whether a line of `length` tokens wins under this grid's rule.
+    pub fn is_winning_length(&self, length: usize) -> bool {
+        match self.win_rule {
+            WinRule::AtLeast => length >= self.win_length,
+            WinRule::Exactly => length == self.win_length,
+        }
+    }
+
+    pub fn contains(&self, cell: Cell) -> bool {
+        cell.row < self.height && cell.column < self.width
+    }
+
+    pub fn get(&self, cell: Cell) -> Option<BoardToken> {
+        if self.contains(cell) {
+            self.cells[cell.row * self.width + cell.column].clone()
+        } else {
+            None
+        }
+    }
+
+    pub fn play(&mut self, cell: Cell, token: BoardToken) -> Result<(), GridError> {
+        if !self.contains(cell) {
+            return Err(GridError::OutOfBounds(cell));
+        }
+        let square = &mut self.cells[cell.row * self.width + cell.column];
+        if square.is_some() {
+            return Err(GridError::Occupied(cell));
+        }
+        *square = Some(token);
+        Ok(())
+    }
+
+    // Every cell in reading order with its contents.
+    pub fn cells(&self) -> impl Iterator<Item = (Cell, Option<BoardToken>)> + '_ {
+        self.cells.iter().enumerate().map(|(index, token)| {
+            (
+                Cell::new(index / self.width, index % self.width),
+                token.clone(),
+            )
+        })
+    }
+
+    pub fn empty_cells(&self) -> Vec<Cell> {
+        self.cells()
+            .filter(|(_, token)| token.is_none())
+            .map(|(cell, _)| cell)
+            .collect()
+    }
+
+    pub fn legal_moves(&self) -> Vec<Cell> {
+        if self.winner().is_some() {
+            Vec::new()
+        } else {
+            self.empty_cells()
+        }
+    }
+
+    pub fn token_count(&self, token: &BoardToken) -> usize {
+        self.cells
+            .iter()
+            .filter(|cell| cell.as_ref() == Some(token))
+            .count()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.cells.iter().all(Option::is_some)
+    }
+
+    // All the maximal runs that win, in reading order of their first cell.
+    pub fn runs(&self) -> Vec<Run> {
+        let mut runs = Vec::new();
+        for (start, token) in self.cells() {
+            let Some(token) = token else {
+                continue;
+            };
+            for direction in DIRECTIONS {
+                // Only count each run from its first cell.
+                let (rows, columns) = direction;
+                if let Some(before) = start.step((-rows, -columns)) {
+                    if self.get(before).as_ref() == Some(&token) {
+                        continue;
+                    }
+                }
+                let mut cells = vec![start];
+                while let Some(next) = cells.last().unwrap().step(direction) {
+                    if self.get(next).as_ref() != Some(&token) {
+                        break;
+                    }
+                    cells.push(next);
+                }
+                if self.is_winning_length(cells.len()) {
+                    runs.push(Run {
+                        token: token.clone(),
+                        cells,
+                    });
+                }
+            }
+        }
+        runs
+    }
+
+    pub fn winner(&self) -> Option<Run> {
+        self.runs().into_iter().next()
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_full() || self.winner().is_some()
+    }
+}
+
+impl Board for Grid {
+    type Token = BoardToken;
+    type Move = Cell;
+    type Line = Run;
+    type Error = GridError;
+
+    fn legal_moves(&self, _token: &BoardToken) -> Vec<Cell> {
+        Grid::legal_moves(self)
+    }
+
+    fn play_move(&mut self, token: &BoardToken, cell: &Cell) -> Result<(), GridError> {
+        self.play(*cell, token.clone())
+    }
+
+    // The first run decides who wins; the rest are listed with it.
+    fn won_by(&self, rules: RuleSet) -> Option<(BoardToken, Vec<Run>)> {
+        let runs = self.runs();
+        let winner = rules.winner(&runs.first()?.token);
+        Some((winner, runs))
+    }
+
+    fn is_finished(&self) -> bool {
+        self.is_full()
+    }
+
+    fn move_prompt(&self, _token: &BoardToken) -> String {
+        let last = Cell::new(self.height - 1, self.width - 1);
+        format!("Enter a cell (A1-{})", last)
+    }
+}
+
+fn column_letter(column: usize) -> char {
+    (b'A' + column as u8) as char
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", column_letter(self.column), self.row + 1)
+    }
+}
+
+impl FromStr for Cell {
+    type Err = GridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let bad = || GridError::BadCell(s.into());
+        let mut chars = s.chars();
+        let letter = chars.next().ok_or_else(bad)?.to_ascii_uppercase();
+        if !letter.is_ascii_uppercase() {
+            return Err(bad());
+        }
+        let row = chars.as_str().trim_start_matches([' ', ',']);
+        let row: usize = row.parse().map_err(|_| bad())?;
+        if row == 0 {
+            return Err(bad());
+        }
+        Ok(Cell::new(row - 1, (letter as u8 - b'A') as usize))
+    }
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::BadSize {
+                width,
+                height,
+                win_length,
+            } => write!(
+                f,
+                "can't make a {}x{} board won with {} in a row",
+                width, height, win_length
+            ),
+            GridError::OutOfBounds(cell) => write!(f, "{} is off the board", cell),
+            GridError::Occupied(cell) => write!(f, "{} is already occupied", cell),
+            GridError::BadCell(s) => write!(f, "{:?} is not a cell such as B3", s),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "  ")?;
+        for column in 0..self.width {
+            write!(f, " {}", column_letter(column))?;
+        }
+        writeln!(f)?;
+        for row in 0..self.height {
+            write!(f, "{:>2}", row + 1)?;
+            for column in 0..self.width {
+                let c = match self.get(Cell::new(row, column)) {
+                    Some(token) => token.to_string(),
+                    None => ".".into(),
+                };
+                write!(f, " {}", c)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::{RandomPlayer, ScriptedPlayer};
+    use crate::{Game, GameResult, Win};
+
+    fn grid(rows: &[&str], win_length: usize) -> Grid {
+        let mut grid = Grid::new(rows[0].len(), rows.len(), win_length).unwrap();
+        for (row, line) in rows.iter().enumerate() {
+            for (column, c) in line.chars().enumerate() {
+                let token = match c {
+                    'X' => BoardToken::Cross,
+                    'O' => BoardToken::Nought,
+                    _ => continue,
+                };
+                grid.play(Cell::new(row, column), token).unwrap();
+            }
+        }
+        grid
+    }
+
+    #[test]
+    pub fn test_bad_sizes() {
+        assert!(Grid::new(0, 3, 3).is_err());
+        assert!(Grid::new(27, 3, 3).is_err());
+        assert!(Grid::new(4, 4, 5).is_err());
+        assert!(Grid::new(4, 4, 0).is_err());
+        assert!(Grid::new(15, 15, 5).is_ok());
+    }
+
+    #[test]
+    pub fn test_play() {
+        let mut grid = Grid::new(4, 4, 4).unwrap();
+        grid.play(Cell::new(3, 3), BoardToken::Cross).unwrap();
+        assert_eq!(Some(BoardToken::Cross), grid.get(Cell::new(3, 3)));
+        assert_eq!(
+            Err(GridError::Occupied(Cell::new(3, 3))),
+            grid.play(Cell::new(3, 3), BoardToken::Nought)
+        );
+        assert_eq!(
+            Err(GridError::OutOfBounds(Cell::new(4, 0))),
+            grid.play(Cell::new(4, 0), BoardToken::Nought)
+        );
+        assert_eq!(15, grid.empty_cells().len());
+        assert_eq!(1, grid.token_count(&BoardToken::Cross));
+    }
+
+    #[test]
+    pub fn test_runs() {
+        assert_eq!(None, grid(&["XXX.", "OO..", "....", "...."], 4).winner());
+        let grid = grid(
+            &[
+                "X...O", //
+                ".X..O", //
+                "..X.O", //
+                "...XO", //
+                "....X",
+            ],
+            4,
+        );
+        let runs = grid.runs();
+        assert_eq!(2, runs.len());
+        assert_eq!(BoardToken::Cross, runs[0].token);
+        assert_eq!(5, runs[0].cells.len());
+        assert_eq!(BoardToken::Nought, runs[1].token);
+        assert_eq!(
+            vec![
+                Cell::new(0, 4),
+                Cell::new(1, 4),
+                Cell::new(2, 4),
+                Cell::new(3, 4)
+            ],
+            runs[1].cells
+        );
+        assert_eq!(Some(runs[0].clone()), grid.winner());
+    }
+
+    #[test]
+    pub fn test_exact_win_rule() {
+        let rows = ["XXXXXX.", "OOOOO.."];
+        let at_least = grid(&rows, 5);
+        assert_eq!(2, at_least.runs().len());
+        assert_eq!(
+            Some(BoardToken::Cross),
+            at_least.winner().map(|run| run.token)
+        );
+
+        // Six in a row is too many, so only the noughts have won.
+        let mut exactly = Grid::new_with_rule(7, 2, 5, WinRule::Exactly).unwrap();
+        for (cell, token) in at_least.cells() {
+            if let Some(token) = token {
+                exactly.play(cell, token).unwrap();
+            }
+        }
+        assert_eq!(
+            vec![BoardToken::Nought],
+            exactly
+                .runs()
+                .into_iter()
+                .map(|run| run.token)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    pub fn test_cell_names() {
+        assert_eq!("A1", Cell::new(0, 0).to_string());
+        assert_eq!("H8", Cell::new(7, 7).to_string());
+        assert_eq!(Ok(Cell::new(14, 14)), "o15".parse());
+        assert_eq!(Ok(Cell::new(7, 7)), "h 8".parse());
+        assert_eq!(Ok(Cell::new(7, 7)), "H,8".parse());
+        assert!("15".parse::<Cell>().is_err());
+        assert!("A0".parse::<Cell>().is_err());
+        assert!("".parse::<Cell>().is_err());
+    }
+
+    #[test]
+    pub fn test_display() {
+        let grid = grid(&["X..", ".O."], 2);
+        assert_eq!("   A B C\n 1 X . .\n 2 . O .\n", grid.to_string());
+    }
+
+    fn cells(names: &[&str]) -> Vec<Cell> {
+        names.iter().map(|name| name.parse().unwrap()).collect()
+    }
+
+    fn game(grid: Grid, moves1: &[&str], moves2: &[&str]) -> Game<Grid> {
+        let mut game = Game::new_with_board(grid);
+        game.set_verbose(false);
+        game.register_player(ScriptedPlayer::new(
+            "Yasmin",
+            BoardToken::Cross,
+            &cells(moves1),
+        ))
+        .unwrap();
+        game.register_player(ScriptedPlayer::new(
+            "Mummy",
+            BoardToken::Nought,
+            &cells(moves2),
+        ))
+        .unwrap();
+        game
+    }
+
+    fn played(game: &Game<Grid>) -> Vec<Cell> {
+        game.history().iter().map(|m| m.location).collect()
+    }
+
+    #[test]
+    pub fn test_four_in_a_row() {
+        let mut game = game(
+            Grid::new(4, 4, 4).unwrap(),
+            &["A1", "B2", "C3", "D4"],
+            &["A2", "A3", "A4"],
+        );
+        let result = game.play().unwrap();
+        assert_eq!(
+            GameResult::Player1(Win {
+                token: BoardToken::Cross,
+                lines: vec![Run {
+                    token: BoardToken::Cross,
+                    cells: cells(&["A1", "B2", "C3", "D4"]),
+                }],
+                forfeit: false,
+            }),
+            result
+        );
+        assert_eq!(7, game.history().len());
+    }
+
+    #[test]
+    pub fn test_taken_cells_are_asked_again() {
+        let mut game = game(
+            Grid::new(5, 2, 2).unwrap(),
+            &["A1", "E2", "C1"],
+            &["A1", "F1", "B2", "C2"],
+        );
+        // Mummy's first two choices are taken and off the grid.
+        let result = game.play().unwrap();
+        assert!(matches!(result, GameResult::Player2(_)));
+        assert_eq!(cells(&["A1", "B2", "E2", "C2"]), played(&game));
+    }
+
+    #[test]
+    pub fn test_tie_and_forfeit() {
+        let mut tied = game(Grid::new(2, 1, 2).unwrap(), &["A1"], &["B1"]);
+        assert_eq!(GameResult::Tie, tied.play().unwrap());
+
+        let mut forfeited = game(Grid::new(3, 3, 3).unwrap(), &["B2"], &["A1"]);
+        let result = forfeited.play().unwrap();
+        assert!(matches!(
+            result,
+            GameResult::Player2(Win { forfeit: true, .. })
+        ));
+    }
+
+    #[test]
+    pub fn test_random_players_fill_a_large_grid() {
+        let mut game = Game::new_with_board(Grid::new(15, 15, 5).unwrap());
+        game.set_verbose(false);
+        let mut player1 = RandomPlayer::new("one", BoardToken::Cross);
+        player1.set_seed(1);
+        let mut player2 = RandomPlayer::new("two", BoardToken::Nought);
+        player2.set_seed(2);
+        game.register_player(player1).unwrap();
+        game.register_player(player2).unwrap();
+        let result = game.play().unwrap();
+        let runs = game.board().runs();
+        match result {
+            GameResult::Tie => assert!(game.board().is_full() && runs.is_empty()),
+            GameResult::Player1(win) | GameResult::Player2(win) => assert_eq!(runs, win.lines),
+        }
+    }
+}
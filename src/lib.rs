@@ -1,40 +1,120 @@
+// Writes a line to the game's output when it is verbose. The game carries on
+// if the output can't be written to.
+macro_rules! say {
+    ($game:ident, $($arg:tt)*) => {
+        if $game.verbose {
+            let _ = writeln!($game.output, $($arg)*);
+        }
+    };
+}
+
 pub mod board;
+pub mod error;
+pub mod event;
+pub mod gomoku;
+pub mod grid;
+pub mod notakto;
+pub mod notation;
+pub mod numerical;
 pub mod player;
+pub mod record;
+pub mod replay;
+pub mod rules;
+pub mod search;
+pub mod ultimate;
+pub mod wild;
 
 use board::*;
+use event::*;
 use player::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use record::*;
+use rules::RuleSet;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+pub use error::Error;
 
-enum Turn {
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Turn {
     Player1,
     Player2,
 }
 
-#[derive(Debug, PartialEq)]
-pub enum GameResult {
-    Player1,
-    Player2,
+#[derive(Debug, PartialEq, Clone)]
+pub struct Move<B: Board = GameBoard> {
+    pub number: usize,
+    pub player: Turn,
+    pub token: B::Token,
+    pub location: B::Move,
+}
+
+// `lines` is empty when the game was won because the opponent forfeited.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Win<B: Board = GameBoard> {
+    pub token: B::Token,
+    pub lines: Vec<B::Line>,
+    pub forfeit: bool,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum GameResult<B: Board = GameBoard> {
+    Player1(Win<B>),
+    Player2(Win<B>),
     Tie,
 }
 
-pub struct Game {
-    player1: Option<ScriptedPlayer>,
-    player2: Option<ScriptedPlayer>,
+// What to do when a player tries an illegal move or has no moves left.
+// `Retry(n)` asks the player again up to n times before they forfeit.
+#[derive(Debug, PartialEq, Clone)]
+pub enum MovePolicy {
+    Forfeit,
+    Retry(usize),
+    Skip,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GameError<T = BoardToken> {
+    PlayerNotRegistered(Turn),
+    TooManyPlayers,
+    DuplicateToken(T),
+    WrongToken { turn: Turn, expected: T, played: T },
+    GameOver,
+    Stalled,
+}
+
+// Runs a game between two players on any kind of board, the classic board
+// unless made with `new_with_board`.
+pub struct Game<B: Board = GameBoard> {
+    player1: Option<Box<dyn Player<B>>>,
+    player2: Option<Box<dyn Player<B>>>,
     next_turn: Turn,
-    board: GameBoard,
+    start: B,
+    board: B,
+    history: Vec<Move<B>>,
+    undone: Vec<Move<B>>,
+    started: u64,
+    verbose: bool,
+    output: Box<dyn Write>,
+    illegal_move_policy: MovePolicy,
+    exhausted_policy: MovePolicy,
+    failures: usize,
+    skips: usize,
+    forfeited: Option<Turn>,
+    observers: Vec<Box<dyn Observer<B>>>,
+    seed: Option<u64>,
+    rules: RuleSet,
 }
 
 pub struct InteractiveGame {
-    player1: Option<InteractivePlayer>,
-    player2: Option<InteractivePlayer>,
-    next_turn: Turn,
-    board: GameBoard,
+    game: Game,
 }
 
 pub struct RandomGame {
-    player1: Option<InteractivePlayer>,
-    player2: Option<ScriptedPlayer>,
-    next_turn: Turn,
-    board: GameBoard,
+    game: Game,
+    difficulty: Option<Difficulty>,
+    seed: Option<u64>,
 }
 
 impl Default for Game {
@@ -45,90 +125,463 @@ impl Default for Game {
 
 impl Game {
     pub fn new() -> Self {
+        Self::new_with_board(GameBoard::default())
+    }
+
+    // Records are only kept of games on the classic board.
+    pub fn record(&self) -> GameRecord {
+        GameRecord {
+            player1: RecordedPlayer {
+                id: self.player1().id().into(),
+                token: self.player1().token(),
+            },
+            player2: RecordedPlayer {
+                id: self.player2().id().into(),
+                token: self.player2().token(),
+            },
+            moves: self.recorded_moves(),
+            started: self.started,
+            updated: record::now(),
+            seed: self.seed,
+            rules: self.rules,
+            forfeit: self.forfeited,
+        }
+    }
+
+    // The history with a `None` for every turn that was skipped, so that the
+    // players strictly alternate.
+    fn recorded_moves(&self) -> Vec<Option<BoardLocation>> {
+        let mut moves = Vec::new();
+        let mut expected = Turn::Player1;
+        for m in &self.history {
+            if m.player != expected {
+                moves.push(None);
+            }
+            moves.push(Some(m.location.clone()));
+            expected = m.player.other();
+        }
+        if self.next_turn != expected {
+            moves.push(None);
+        }
+        moves
+    }
+
+    // Restores the board, history, turn and any forfeit from a record so that
+    // play can carry on. The registered players must hold the recorded tokens.
+    pub fn resume(&mut self, record: &GameRecord) -> Result<(), RecordError> {
+        if self.player1().token() != record.player1.token
+            || self.player2().token() != record.player2.token
+        {
+            return Err(RecordError::PlayerMismatch);
+        }
+        self.start = GameBoard::default();
+        self.board = record.board()?;
+        self.history = record
+            .moves
+            .iter()
+            .enumerate()
+            .filter_map(|(index, location)| {
+                Some((
+                    index,
+                    if index.is_multiple_of(2) {
+                        Turn::Player1
+                    } else {
+                        Turn::Player2
+                    },
+                    location.clone()?,
+                ))
+            })
+            .enumerate()
+            .map(|(number, (index, player, location))| Move {
+                number: number + 1,
+                player,
+                token: record.token_for_move(index),
+                location,
+            })
+            .collect();
+        self.undone.clear();
+        self.forfeited = record.forfeit;
+        self.next_turn = if record.moves.len().is_multiple_of(2) {
+            Turn::Player1
+        } else {
+            Turn::Player2
+        };
+        self.started = record.started;
+        self.seed = record.seed;
+        self.set_rules(record.rules);
+        Ok(())
+    }
+}
+
+impl<B: Board> Game<B> {
+    // Starts from `board`, which needn't be empty. Undoing goes no further
+    // back than it.
+    pub fn new_with_board(board: B) -> Self {
         Game {
             player1: None,
             player2: None,
             next_turn: Turn::Player1,
-            board: GameBoard::default(),
+            start: board.clone(),
+            board,
+            history: Vec::new(),
+            undone: Vec::new(),
+            started: record::now(),
+            verbose: true,
+            output: Box::new(io::stdout()),
+            illegal_move_policy: MovePolicy::Retry(3),
+            exhausted_policy: MovePolicy::Forfeit,
+            failures: 0,
+            skips: 0,
+            forfeited: None,
+            observers: Vec::new(),
+            seed: None,
+            rules: RuleSet::default(),
+        }
+    }
+
+    // The first player registered moves first. The two players must hold
+    // different tokens. Players are told the game's rules as they join.
+    pub fn register_player<P: Player<B> + 'static>(
+        &mut self,
+        mut player: P,
+    ) -> Result<(), GameError<B::Token>> {
+        player.set_rules(self.rules);
+        match (&self.player1, &self.player2) {
+            (None, _) => self.player1 = Some(Box::new(player)),
+            (Some(player1), None) => {
+                if player1.token() == player.token() {
+                    return Err(GameError::DuplicateToken(player.token()));
+                }
+                self.player2 = Some(Box::new(player));
+            }
+            (Some(_), Some(_)) => return Err(GameError::TooManyPlayers),
         }
+        Ok(())
+    }
+
+    // Turns the board and messages written while playing on or off.
+    pub fn set_verbose(&mut self, verbose: bool) {
+        self.verbose = verbose;
+    }
+
+    // Notes the seed the random players were created with so that it is shown
+    // when the game starts and kept in the record. The game itself makes no
+    // random choices.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
     }
 
-    pub fn register_player(&mut self, player: ScriptedPlayer) {
-        match self.player1 {
-            None => self.player1 = Some(player),
-            _ => self.player2 = Some(player),
+    // Defaults to standard rules. Players who have already joined are told
+    // about the change.
+    pub fn set_rules(&mut self, rules: RuleSet) {
+        self.rules = rules;
+        for player in [&mut self.player1, &mut self.player2].into_iter().flatten() {
+            player.set_rules(rules);
         }
     }
 
-    pub fn play(&mut self) -> GameResult {
+    pub fn rules(&self) -> RuleSet {
+        self.rules
+    }
+
+    // Observers are told about everything that happens in the game from then
+    // on, in the order they were added.
+    pub fn add_observer<O: Observer<B> + 'static>(&mut self, observer: O) {
+        self.observers.push(Box::new(observer));
+    }
+
+    // Where the board and messages are written. Defaults to standard output.
+    pub fn set_output<W: Write + 'static>(&mut self, output: W) {
+        self.output = Box::new(output);
+    }
+
+    // Also used for players other than people who answer with something that
+    // isn't a move. Defaults to retrying three times.
+    pub fn set_illegal_move_policy(&mut self, policy: MovePolicy) {
+        self.illegal_move_policy = policy;
+    }
+
+    // Defaults to forfeiting.
+    pub fn set_exhausted_policy(&mut self, policy: MovePolicy) {
+        self.exhausted_policy = policy;
+    }
+
+    pub fn play(&mut self) -> Result<GameResult<B>, GameError<B::Token>> {
+        if self.player1.is_none() {
+            return Err(GameError::PlayerNotRegistered(Turn::Player1));
+        }
+        if self.player2.is_none() {
+            return Err(GameError::PlayerNotRegistered(Turn::Player2));
+        }
+        if self.result().is_some() {
+            return Err(GameError::GameOver);
+        }
+        if let Some(seed) = self.seed {
+            say!(self, "Random seed: {}", seed);
+        }
+        if self.rules == RuleSet::Misere {
+            say!(self, "Misère rules: whoever makes three in a row loses.");
+        }
+        self.emit(GameEvent::GameStarted {
+            player1: RecordedPlayer {
+                id: self.player1().id().into(),
+                token: self.player1().token(),
+            },
+            player2: RecordedPlayer {
+                id: self.player2().id().into(),
+                token: self.player2().token(),
+            },
+            board: self.board.clone(),
+        });
         loop {
-            print!("{}", &self.board);
-            self.player_move();
+            self.show_board();
             if let Some(res) = self.result() {
-                print!("{}", &self.board);
-                return res;
+                self.emit(GameEvent::GameOver(res.clone()));
+                let message = match res {
+                    GameResult::Player1(_) => format!("{} wins!", self.player1().id()),
+                    GameResult::Player2(_) => format!("{} wins!", self.player2().id()),
+                    GameResult::Tie => "Well played. It's a tie!".into(),
+                };
+                say!(self, "{}", message);
+                return Ok(res);
             }
+            self.player_move()?;
+        }
+    }
+
+    fn emit(&mut self, event: GameEvent<B>) {
+        for observer in &mut self.observers {
+            observer.notify(&event);
+        }
+    }
+
+    fn show_board(&mut self) {
+        if self.verbose {
+            let _ = write!(self.output, "{}", self.board);
+        }
+    }
+
+    fn player1(&self) -> &dyn Player<B> {
+        self.player1.as_deref().expect("Player1 not registered")
+    }
+
+    fn player2(&self) -> &dyn Player<B> {
+        self.player2.as_deref().expect("Player2 not registered")
+    }
+
+    pub fn board(&self) -> &B {
+        &self.board
+    }
+
+    pub fn history(&self) -> &[Move<B>] {
+        &self.history
+    }
+
+    pub fn undo(&mut self) -> Option<Move<B>> {
+        let last = self.history.pop()?;
+        self.board = self.start.clone();
+        for m in &self.history {
+            self.board
+                .play_move(&m.token, &m.location)
+                .expect("history only contains legal moves");
         }
+        self.next_turn = last.player;
+        self.undone.push(last.clone());
+        Some(last)
     }
 
-    fn player_move(&mut self) {
-        match self.next_turn {
-            Turn::Player1 => {
-                println!("{}, it's your turn!", self.player1.as_ref().unwrap().id);
-                if let Ok((token, location)) = self.player1.as_mut().unwrap().play() {
-                    self.board.play(location, token);
-                    self.next_turn = Turn::Player2;
+    pub fn redo(&mut self) -> Option<Move<B>> {
+        let next = self.undone.pop()?;
+        self.board
+            .play_move(&next.token, &next.location)
+            .expect("redone moves were legal when first played");
+        self.next_turn = next.player.other();
+        self.history.push(next.clone());
+        Some(next)
+    }
+
+    fn player_move(&mut self) -> Result<(), GameError<B::Token>> {
+        let turn = self.next_turn;
+        self.emit(GameEvent::TurnStarted {
+            turn,
+            board: self.board.clone(),
+        });
+        let player = match turn {
+            Turn::Player1 => self.player1.as_deref_mut(),
+            Turn::Player2 => self.player2.as_deref_mut(),
+        };
+        let player = player.ok_or(GameError::PlayerNotRegistered(turn))?;
+        say!(self, "{}, it's your turn!", player.id());
+        let policy = match player.next_action(&self.board) {
+            Ok(PlayerAction::Play(token, location)) => {
+                if token != player.token() {
+                    return Err(GameError::WrongToken {
+                        turn,
+                        expected: player.token(),
+                        played: token,
+                    });
+                }
+                if let Err(err) = self.board.play_move(&token, &location) {
+                    say!(self, "Sorry, {}.", err);
+                    self.emit(GameEvent::IllegalMove { turn, location });
+                    self.illegal_move_policy.clone()
                 } else {
-                    panic!("Player1 out of moves");
+                    let played = Move {
+                        number: self.history.len() + 1,
+                        player: turn,
+                        token,
+                        location,
+                    };
+                    self.history.push(played.clone());
+                    self.undone.clear();
+                    self.end_turn(turn);
+                    self.skips = 0;
+                    self.emit(GameEvent::MovePlayed {
+                        played,
+                        board: self.board.clone(),
+                    });
+                    return Ok(());
                 }
             }
-            Turn::Player2 => {
-                println!("{}, it's your turn!", self.player2.as_ref().unwrap().id);
-                if let Ok((token, location)) = self.player2.as_mut().unwrap().play() {
-                    self.board.play(location, token);
-                    self.next_turn = Turn::Player1;
-                } else {
-                    panic!("Player2 out of moves")
+            Ok(PlayerAction::Undo) => {
+                // Take back moves until it is this player's turn again. A
+                // player who hasn't moved yet has nothing of theirs to take
+                // back, and mustn't take back their opponent's moves.
+                if self.history.iter().all(|m| m.player != turn) {
+                    say!(self, "Nothing to undo.");
+                    return Ok(());
                 }
+                while self.undo().is_some_and(|m| m.player != turn) {}
+                return Ok(());
             }
-        }
+            Ok(PlayerAction::Redo) => {
+                if self.redo().is_none() {
+                    say!(self, "Nothing to redo.");
+                }
+                while self.next_turn != turn && self.redo().is_some() {}
+                return Ok(());
+            }
+            Err(PlayerError::InvalidLocation) if player.is_interactive() => {
+                say!(self, "Sorry. Didn't understand that. Try again.");
+                return Ok(());
+            }
+            Err(PlayerError::InvalidLocation) => {
+                say!(self, "{} didn't give a move on the board.", player.id());
+                self.illegal_move_policy.clone()
+            }
+            Err(PlayerError::NoMoreMoves) => {
+                say!(self, "{} has run out of moves.", player.id());
+                self.emit(GameEvent::OutOfMoves { turn });
+                self.exhausted_policy.clone()
+            }
+        };
+        self.adjudicate(turn, policy)
     }
 
-    fn result(&self) -> Option<GameResult> {
-        if self.board.is_top_row_win()
-            || self.board.is_left_column_win()
-            || self.board.is_left_right_diagonal_win()
-        {
-            if self.board.get(BoardLocation::TopLeft).unwrap()
-                == self.player1.as_ref().unwrap().token
-            {
-                Some(GameResult::Player1)
-            } else {
-                Some(GameResult::Player2)
+    fn adjudicate(&mut self, turn: Turn, policy: MovePolicy) -> Result<(), GameError<B::Token>> {
+        self.failures += 1;
+        match policy {
+            MovePolicy::Retry(limit) if self.failures <= limit => {
+                say!(self, "Choose another.");
             }
-        } else if self.board.is_middle_row_win()
-            || self.board.is_centre_column_win()
-            || self.board.is_right_left_diagonal_win()
-        {
-            if self.board.get(BoardLocation::MiddleCentre).unwrap()
-                == self.player1.as_ref().unwrap().token
-            {
-                Some(GameResult::Player1)
-            } else {
-                Some(GameResult::Player2)
+            MovePolicy::Forfeit | MovePolicy::Retry(_) => {
+                say!(self, "That's a forfeit.");
+                self.forfeited = Some(turn);
+                self.emit(GameEvent::Forfeit { turn });
             }
-        } else if self.board.is_bottom_row_win() || self.board.is_right_column_win() {
-            if self.board.get(BoardLocation::BottomRight).unwrap()
-                == self.player1.as_ref().unwrap().token
-            {
-                Some(GameResult::Player1)
-            } else {
-                Some(GameResult::Player2)
+            MovePolicy::Skip => {
+                say!(self, "Skipping to the next player.");
+                self.end_turn(turn);
+                self.emit(GameEvent::TurnSkipped { turn });
+                // Both players skipping in a row means nobody can move.
+                self.skips += 1;
+                if self.skips >= 2 {
+                    return Err(GameError::Stalled);
+                }
             }
-        } else if self.board.is_full() {
-            Some(GameResult::Tie)
-        } else {
-            None
+        }
+        Ok(())
+    }
+
+    fn end_turn(&mut self, turn: Turn) {
+        self.next_turn = turn.other();
+        self.failures = 0;
+    }
+
+    fn result(&self) -> Option<GameResult<B>> {
+        if let Some(loser) = self.forfeited {
+            let winner = loser.other();
+            let win = Win {
+                token: match winner {
+                    Turn::Player1 => self.player1().token(),
+                    Turn::Player2 => self.player2().token(),
+                },
+                lines: Vec::new(),
+                forfeit: true,
+            };
+            return Some(match winner {
+                Turn::Player1 => GameResult::Player1(win),
+                Turn::Player2 => GameResult::Player2(win),
+            });
+        }
+        self.rules.result(&self.board, &self.player1().token())
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for GameError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::PlayerNotRegistered(turn) => {
+                write!(f, "{} has not been registered", turn)
+            }
+            GameError::TooManyPlayers => write!(f, "two players are already registered"),
+            GameError::DuplicateToken(token) => {
+                write!(f, "both players can't play {}", token)
+            }
+            GameError::WrongToken {
+                turn,
+                expected,
+                played,
+            } => write!(
+                f,
+                "{} played {} but their token is {}",
+                turn, played, expected
+            ),
+            GameError::GameOver => write!(f, "the game is already over"),
+            GameError::Stalled => write!(f, "neither player is able to move"),
+        }
+    }
+}
+
+impl<T: fmt::Debug + fmt::Display> std::error::Error for GameError<T> {}
+
+impl Turn {
+    pub fn other(&self) -> Turn {
+        match self {
+            Turn::Player1 => Turn::Player2,
+            Turn::Player2 => Turn::Player1,
+        }
+    }
+}
+
+impl fmt::Display for Turn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Turn::Player1 => write!(f, "player 1"),
+            Turn::Player2 => write!(f, "player 2"),
+        }
+    }
+}
+
+impl<B: Board> GameResult<B> {
+    pub fn win(&self) -> Option<&Win<B>> {
+        match self {
+            GameResult::Player1(win) | GameResult::Player2(win) => Some(win),
+            GameResult::Tie => None,
         }
     }
 }
@@ -141,102 +594,30 @@ impl Default for InteractiveGame {
 
 impl InteractiveGame {
     pub fn new() -> Self {
-        Self {
-            player1: None,
-            player2: None,
-            next_turn: Turn::Player1,
-            board: GameBoard::default(),
-        }
+        Self { game: Game::new() }
     }
 
-    pub fn register_player(&mut self, player: InteractivePlayer) {
-        match self.player1 {
-            None => self.player1 = Some(player),
-            _ => self.player2 = Some(player),
-        }
+    pub fn register_player<R, W>(
+        &mut self,
+        player: InteractivePlayer<R, W>,
+    ) -> Result<(), GameError>
+    where
+        R: BufRead + 'static,
+        W: Write + 'static,
+    {
+        self.game.register_player(player)
     }
 
-    pub fn play(&mut self) -> GameResult {
-        loop {
-            print!("{}", &self.board);
-            self.player_move();
-            if let Some(res) = self.result() {
-                print!("{}", &self.board);
-                match res {
-                    GameResult::Player1 => println!("{} wins!", self.player1.as_ref().unwrap().id),
-                    GameResult::Player2 => println!("{} wins!", self.player2.as_ref().unwrap().id),
-                    GameResult::Tie => println!("Well played. It's a tie!"),
-                }
-                return res;
-            }
-        }
+    pub fn set_output<W: Write + 'static>(&mut self, output: W) {
+        self.game.set_output(output);
     }
 
-    fn player_move(&mut self) {
-        match self.next_turn {
-            Turn::Player1 => {
-                println!("{}, it's your turn!", self.player1.as_ref().unwrap().id);
-                if let Ok((token, location)) = self.player1.as_mut().unwrap().play() {
-                    if self.board.play(location, token).is_ok() {
-                        self.next_turn = Turn::Player2;
-                    } else {
-                        println!("That location is not free. Choose another.");
-                    }
-                } else {
-                    println!("Sorry. Didn't understand that. Try again.");
-                }
-            }
-            Turn::Player2 => {
-                println!("{}, it's your turn!", self.player2.as_ref().unwrap().id);
-                if let Ok((token, location)) = self.player2.as_mut().unwrap().play() {
-                    if self.board.play(location, token).is_ok() {
-                        self.next_turn = Turn::Player1;
-                    } else {
-                        println!("That location is not free. Choose another.");
-                    }
-                } else {
-                    println!("Sorry. Didn't understand that. Try again.");
-                }
-            }
-        }
+    pub fn add_observer<O: Observer + 'static>(&mut self, observer: O) {
+        self.game.add_observer(observer);
     }
 
-    fn result(&self) -> Option<GameResult> {
-        if self.board.is_top_row_win()
-            || self.board.is_left_column_win()
-            || self.board.is_left_right_diagonal_win()
-        {
-            if self.board.get(BoardLocation::TopLeft).unwrap()
-                == self.player1.as_ref().unwrap().token
-            {
-                Some(GameResult::Player1)
-            } else {
-                Some(GameResult::Player2)
-            }
-        } else if self.board.is_middle_row_win()
-            || self.board.is_centre_column_win()
-            || self.board.is_right_left_diagonal_win()
-        {
-            if self.board.get(BoardLocation::MiddleCentre).unwrap()
-                == self.player1.as_ref().unwrap().token
-            {
-                Some(GameResult::Player1)
-            } else {
-                Some(GameResult::Player2)
-            }
-        } else if self.board.is_bottom_row_win() || self.board.is_right_column_win() {
-            if self.board.get(BoardLocation::BottomRight).unwrap()
-                == self.player1.as_ref().unwrap().token
-            {
-                Some(GameResult::Player1)
-            } else {
-                Some(GameResult::Player2)
-            }
-        } else if self.board.is_full() {
-            Some(GameResult::Tie)
-        } else {
-            None
-        }
+    pub fn play(&mut self) -> Result<GameResult, GameError> {
+        self.game.play()
     }
 }
 
@@ -249,98 +630,66 @@ impl Default for RandomGame {
 impl RandomGame {
     pub fn new() -> Self {
         Self {
-            player1: None,
-            player2: Some(ScriptedPlayer::new_random("random", BoardToken::Nought)),
-            next_turn: Turn::Player1,
-            board: GameBoard::default(),
+            game: Game::new(),
+            difficulty: None,
+            seed: None,
         }
     }
 
-    pub fn register_player(&mut self, player: InteractivePlayer) {
-        self.player1 = Some(player);
+    pub fn new_with_difficulty(difficulty: Difficulty) -> Self {
+        Self {
+            game: Game::new(),
+            difficulty: Some(difficulty),
+            seed: None,
+        }
     }
 
-    pub fn play(&mut self) -> GameResult {
-        loop {
-            print!("{}", &self.board);
-            self.player_move();
-            if let Some(res) = self.result() {
-                print!("{}", &self.board);
-                match res {
-                    GameResult::Player1 => println!("{} wins!", self.player1.as_ref().unwrap().id),
-                    GameResult::Player2 => println!("{} wins!", self.player2.as_ref().unwrap().id),
-                    GameResult::Tie => println!("Well played. It's a tie!"),
-                }
-                return res;
-            }
-        }
+    // Seeds the computer player's random choices so that the same moves get
+    // the same replies. Without a seed they come from entropy.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self.game.set_seed(seed);
+        self
     }
 
-    fn player_move(&mut self) {
-        match self.next_turn {
-            Turn::Player1 => {
-                println!("{}, it's your turn!", self.player1.as_ref().unwrap().id);
-                if let Ok((token, location)) = self.player1.as_mut().unwrap().play() {
-                    if self.board.play(location, token).is_ok() {
-                        self.next_turn = Turn::Player2;
-                    } else {
-                        println!("That location is not free. Choose another.");
-                    }
-                } else {
-                    println!("Sorry. Didn't understand that. Try again.");
-                }
-            }
-            Turn::Player2 => {
-                println!("{}, it's your turn!", self.player2.as_ref().unwrap().id);
-                if let Ok((token, location)) = self.player2.as_mut().unwrap().play() {
-                    if self.board.play(location, token).is_ok() {
-                        self.next_turn = Turn::Player1;
-                    } else {
-                        println!("That location is not free. Choose another.");
-                    }
-                } else {
-                    println!("Sorry. Didn't understand that. Try again.");
-                }
-            }
+    pub fn register_player<R, W>(
+        &mut self,
+        player: InteractivePlayer<R, W>,
+    ) -> Result<(), GameError>
+    where
+        R: BufRead + 'static,
+        W: Write + 'static,
+    {
+        self.game.register_player(player)?;
+        let rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        match &self.difficulty {
+            None => self
+                .game
+                .register_player(RandomPlayer::new("random", BoardToken::Nought).with_rng(rng)),
+            Some(difficulty) => self.game.register_player(
+                AiPlayer::new(
+                    &format!("{:?} AI", difficulty),
+                    BoardToken::Nought,
+                    difficulty.clone(),
+                )
+                .with_rng(rng),
+            ),
         }
     }
 
-    fn result(&self) -> Option<GameResult> {
-        if self.board.is_top_row_win()
-            || self.board.is_left_column_win()
-            || self.board.is_left_right_diagonal_win()
-        {
-            if self.board.get(BoardLocation::TopLeft).unwrap()
-                == self.player1.as_ref().unwrap().token
-            {
-                Some(GameResult::Player1)
-            } else {
-                Some(GameResult::Player2)
-            }
-        } else if self.board.is_middle_row_win()
-            || self.board.is_centre_column_win()
-            || self.board.is_right_left_diagonal_win()
-        {
-            if self.board.get(BoardLocation::MiddleCentre).unwrap()
-                == self.player1.as_ref().unwrap().token
-            {
-                Some(GameResult::Player1)
-            } else {
-                Some(GameResult::Player2)
-            }
-        } else if self.board.is_bottom_row_win() || self.board.is_right_column_win() {
-            if self.board.get(BoardLocation::BottomRight).unwrap()
-                == self.player1.as_ref().unwrap().token
-            {
-                Some(GameResult::Player1)
-            } else {
-                Some(GameResult::Player2)
-            }
-        } else if self.board.is_full() {
-            Some(GameResult::Tie)
-        } else {
-            None
-        }
+    pub fn set_output<W: Write + 'static>(&mut self, output: W) {
+        self.game.set_output(output);
+    }
+
+    pub fn add_observer<O: Observer + 'static>(&mut self, observer: O) {
+        self.game.add_observer(observer);
+    }
+
+    pub fn play(&mut self) -> Result<GameResult, GameError> {
+        self.game.play()
     }
 }
 
@@ -354,7 +703,7 @@ mod tests {
         let player1 = ScriptedPlayer::new(
             "Yasmin",
             BoardToken::Cross,
-            &vec![
+            &[
                 BoardLocation::MiddleCentre,
                 BoardLocation::TopCentre,
                 BoardLocation::BottomCentre,
@@ -363,13 +712,19 @@ mod tests {
         let player2 = ScriptedPlayer::new(
             "Mummy",
             BoardToken::Nought,
-            &vec![BoardLocation::TopLeft, BoardLocation::MiddleLeft],
+            &[BoardLocation::TopLeft, BoardLocation::MiddleLeft],
         );
 
-        game.register_player(player1);
-        game.register_player(player2);
-        let result = game.play();
-        assert_eq!(Some(GameResult::Player1), game.result());
+        game.register_player(player1).unwrap();
+        game.register_player(player2).unwrap();
+        let result = game.play().unwrap();
+        let win = Win {
+            token: BoardToken::Cross,
+            lines: vec![BoardLine::CentreColumn],
+            forfeit: false,
+        };
+        assert_eq!(GameResult::Player1(win.clone()), result);
+        assert_eq!(Some(GameResult::Player1(win)), game.result());
     }
 
     #[test]
@@ -378,7 +733,7 @@ mod tests {
         let player1 = ScriptedPlayer::new(
             "Yasmin",
             BoardToken::Cross,
-            &vec![
+            &[
                 BoardLocation::MiddleCentre,
                 BoardLocation::TopCentre,
                 BoardLocation::MiddleRight,
@@ -389,7 +744,7 @@ mod tests {
         let player2 = ScriptedPlayer::new(
             "Mummy",
             BoardToken::Nought,
-            &vec![
+            &[
                 BoardLocation::TopLeft,
                 BoardLocation::BottomCentre,
                 BoardLocation::MiddleLeft,
@@ -397,9 +752,709 @@ mod tests {
             ],
         );
 
-        game.register_player(player1);
-        game.register_player(player2);
-        let result = game.play();
+        game.register_player(player1).unwrap();
+        game.register_player(player2).unwrap();
+        let result = game.play().unwrap();
+        assert_eq!(GameResult::Tie, result);
         assert_eq!(Some(GameResult::Tie), game.result());
     }
+
+    struct FirstFreePlayer {
+        token: BoardToken,
+    }
+
+    impl Player for FirstFreePlayer {
+        fn id(&self) -> &str {
+            "first free"
+        }
+
+        fn token(&self) -> BoardToken {
+            self.token.clone()
+        }
+
+        fn play(&mut self, board: &GameBoard) -> Result<(BoardToken, BoardLocation), PlayerError> {
+            [
+                BoardLocation::TopLeft,
+                BoardLocation::TopCentre,
+                BoardLocation::TopRight,
+                BoardLocation::MiddleLeft,
+                BoardLocation::MiddleCentre,
+                BoardLocation::MiddleRight,
+                BoardLocation::BottomLeft,
+                BoardLocation::BottomCentre,
+                BoardLocation::BottomRight,
+            ]
+            .into_iter()
+            .find(|location| board.get(location.clone()).is_none())
+            .map(|location| (self.token.clone(), location))
+            .ok_or(PlayerError::NoMoreMoves)
+        }
+    }
+
+    #[test]
+    pub fn test_game_with_mixed_players() {
+        let mut game = Game::new();
+        let player1 = ScriptedPlayer::new(
+            "Yasmin",
+            BoardToken::Cross,
+            &[
+                BoardLocation::MiddleLeft,
+                BoardLocation::MiddleCentre,
+                BoardLocation::MiddleRight,
+            ],
+        );
+        let player2 = FirstFreePlayer {
+            token: BoardToken::Nought,
+        };
+
+        game.register_player(player1).unwrap();
+        game.register_player(player2).unwrap();
+        let result = game.play().unwrap();
+        assert_eq!(
+            GameResult::Player1(Win {
+                token: BoardToken::Cross,
+                lines: vec![BoardLine::MiddleRow],
+                forfeit: false,
+            }),
+            result
+        );
+    }
+
+    #[test]
+    pub fn test_minimax_never_loses() {
+        let mut game = Game::new();
+        game.register_player(MinimaxPlayer::new("minimax 1", BoardToken::Cross))
+            .unwrap();
+        game.register_player(MinimaxPlayer::new("minimax 2", BoardToken::Nought))
+            .unwrap();
+        assert_eq!(GameResult::Tie, game.play().unwrap());
+
+        let mut game = Game::new();
+        game.register_player(FirstFreePlayer {
+            token: BoardToken::Cross,
+        })
+        .unwrap();
+        game.register_player(MinimaxPlayer::new("minimax", BoardToken::Nought))
+            .unwrap();
+        assert!(matches!(game.play().unwrap(), GameResult::Player2(_)));
+    }
+
+    #[test]
+    pub fn test_perfect_ai_never_loses() {
+        for first in [true, false] {
+            let mut game = Game::new();
+            let ai = AiPlayer::new("perfect", BoardToken::Nought, Difficulty::Perfect);
+            let opponent = AiPlayer::new("easy", BoardToken::Cross, Difficulty::Easy);
+            if first {
+                game.register_player(ai).unwrap();
+                game.register_player(opponent).unwrap();
+                assert!(!matches!(game.play().unwrap(), GameResult::Player2(_)));
+            } else {
+                game.register_player(opponent).unwrap();
+                game.register_player(ai).unwrap();
+                assert!(!matches!(game.play().unwrap(), GameResult::Player1(_)));
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_misere_game() {
+        let mut game = Game::new();
+        game.set_verbose(false);
+        game.set_rules(RuleSet::Misere);
+        game.register_player(ScriptedPlayer::new(
+            "Yasmin",
+            BoardToken::Cross,
+            &[
+                BoardLocation::TopLeft,
+                BoardLocation::TopCentre,
+                BoardLocation::TopRight,
+            ],
+        ))
+        .unwrap();
+        game.register_player(ScriptedPlayer::new(
+            "Mummy",
+            BoardToken::Nought,
+            &[BoardLocation::MiddleLeft, BoardLocation::BottomRight],
+        ))
+        .unwrap();
+        let result = game.play().unwrap();
+        assert_eq!(
+            GameResult::Player2(Win {
+                token: BoardToken::Nought,
+                lines: vec![BoardLine::TopRow],
+                forfeit: false,
+            }),
+            result
+        );
+        assert_eq!(RuleSet::Misere, game.record().rules);
+    }
+
+    #[test]
+    pub fn test_perfect_ai_never_loses_at_misere() {
+        for seed in 0..4 {
+            let mut game = Game::new();
+            game.set_verbose(false);
+            let mut ai = AiPlayer::new("perfect", BoardToken::Nought, Difficulty::Perfect);
+            ai.set_seed(seed);
+            let mut opponent = AiPlayer::new("medium", BoardToken::Cross, Difficulty::Medium);
+            opponent.set_seed(seed + 100);
+            // The rules reach players whether they join before or after.
+            if seed % 2 == 0 {
+                game.register_player(ai).unwrap();
+                game.set_rules(RuleSet::Misere);
+                game.register_player(opponent).unwrap();
+                assert!(!matches!(game.play().unwrap(), GameResult::Player2(_)));
+            } else {
+                game.register_player(opponent).unwrap();
+                game.register_player(ai).unwrap();
+                game.set_rules(RuleSet::Misere);
+                assert!(!matches!(game.play().unwrap(), GameResult::Player1(_)));
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_double_win() {
+        let mut game = Game::new();
+        let player1 = ScriptedPlayer::new(
+            "Yasmin",
+            BoardToken::Cross,
+            &[
+                BoardLocation::TopCentre,
+                BoardLocation::TopRight,
+                BoardLocation::MiddleLeft,
+                BoardLocation::BottomLeft,
+                BoardLocation::TopLeft,
+            ],
+        );
+        let player2 = ScriptedPlayer::new(
+            "Mummy",
+            BoardToken::Nought,
+            &[
+                BoardLocation::MiddleCentre,
+                BoardLocation::BottomRight,
+                BoardLocation::BottomCentre,
+                BoardLocation::MiddleRight,
+            ],
+        );
+
+        game.register_player(player1).unwrap();
+        game.register_player(player2).unwrap();
+        let result = game.play().unwrap();
+        let win = result.win().unwrap();
+        assert_eq!(BoardToken::Cross, win.token);
+        assert_eq!(vec![BoardLine::TopRow, BoardLine::LeftColumn], win.lines);
+    }
+
+    #[test]
+    pub fn test_history_undo_and_redo() {
+        let mut game = Game::new();
+        game.register_player(ScriptedPlayer::new(
+            "Yasmin",
+            BoardToken::Cross,
+            &[BoardLocation::MiddleCentre, BoardLocation::TopCentre],
+        ))
+        .unwrap();
+        game.register_player(ScriptedPlayer::new(
+            "Mummy",
+            BoardToken::Nought,
+            &[BoardLocation::TopLeft],
+        ))
+        .unwrap();
+        game.player_move().unwrap();
+        game.player_move().unwrap();
+        game.player_move().unwrap();
+        assert_eq!(3, game.history().len());
+        assert_eq!(
+            Move {
+                number: 2,
+                player: Turn::Player2,
+                token: BoardToken::Nought,
+                location: BoardLocation::TopLeft,
+            },
+            game.history()[1]
+        );
+
+        let undone = game.undo().unwrap();
+        assert_eq!(BoardLocation::TopCentre, undone.location);
+        assert_eq!(Turn::Player1, game.next_turn);
+        assert!(game.board().get(BoardLocation::TopCentre).is_none());
+        game.undo().unwrap();
+        assert_eq!(Turn::Player2, game.next_turn);
+        assert!(game.board().get(BoardLocation::TopLeft).is_none());
+        assert_eq!(
+            Some(BoardToken::Cross),
+            game.board().get(BoardLocation::MiddleCentre)
+        );
+
+        let redone = game.redo().unwrap();
+        assert_eq!(BoardLocation::TopLeft, redone.location);
+        assert_eq!(Turn::Player1, game.next_turn);
+        assert_eq!(
+            Some(BoardToken::Nought),
+            game.board().get(BoardLocation::TopLeft)
+        );
+        game.redo().unwrap();
+        assert!(game.redo().is_none());
+        assert_eq!(3, game.history().len());
+        assert_eq!(Turn::Player2, game.next_turn);
+    }
+
+    #[test]
+    pub fn test_new_move_clears_redo() {
+        let mut game = Game::new();
+        game.register_player(ScriptedPlayer::new(
+            "Yasmin",
+            BoardToken::Cross,
+            &[BoardLocation::MiddleCentre, BoardLocation::TopCentre],
+        ))
+        .unwrap();
+        game.register_player(ScriptedPlayer::new("Mummy", BoardToken::Nought, &[]))
+            .unwrap();
+        game.player_move().unwrap();
+        game.undo().unwrap();
+        game.player_move().unwrap();
+        assert!(game.redo().is_none());
+        assert_eq!(1, game.history().len());
+        assert_eq!(BoardLocation::TopCentre, game.history()[0].location);
+    }
+
+    #[test]
+    pub fn test_record_and_resume() {
+        let mut game = Game::new();
+        game.register_player(ScriptedPlayer::new(
+            "Yasmin",
+            BoardToken::Cross,
+            &[BoardLocation::MiddleCentre, BoardLocation::TopCentre],
+        ))
+        .unwrap();
+        game.register_player(ScriptedPlayer::new(
+            "Mummy",
+            BoardToken::Nought,
+            &[BoardLocation::TopLeft],
+        ))
+        .unwrap();
+        game.player_move().unwrap();
+        game.player_move().unwrap();
+        game.player_move().unwrap();
+        let record = game.record();
+        assert_eq!("Yasmin", record.player1.id);
+        assert_eq!(3, record.moves.len());
+
+        let mut resumed = Game::new();
+        resumed
+            .register_player(ScriptedPlayer::new(
+                "Yasmin",
+                BoardToken::Cross,
+                &[BoardLocation::BottomCentre],
+            ))
+            .unwrap();
+        resumed
+            .register_player(ScriptedPlayer::new(
+                "Mummy",
+                BoardToken::Nought,
+                &[BoardLocation::MiddleLeft],
+            ))
+            .unwrap();
+        resumed.resume(&record).unwrap();
+        assert_eq!(game.history(), resumed.history());
+        assert_eq!(game.board(), resumed.board());
+        assert_eq!(Turn::Player2, resumed.next_turn);
+        assert!(matches!(resumed.play().unwrap(), GameResult::Player1(_)));
+    }
+
+    #[test]
+    pub fn test_resume_with_wrong_tokens() {
+        let mut game = Game::new();
+        game.register_player(ScriptedPlayer::new("Yasmin", BoardToken::Cross, &[]))
+            .unwrap();
+        game.register_player(ScriptedPlayer::new("Mummy", BoardToken::Nought, &[]))
+            .unwrap();
+        let record = game.record();
+
+        let mut resumed = Game::new();
+        resumed
+            .register_player(ScriptedPlayer::new("Mummy", BoardToken::Nought, &[]))
+            .unwrap();
+        resumed
+            .register_player(ScriptedPlayer::new("Yasmin", BoardToken::Cross, &[]))
+            .unwrap();
+        assert!(matches!(
+            resumed.resume(&record),
+            Err(RecordError::PlayerMismatch)
+        ));
+    }
+
+    fn occupied_cell_game(policy: MovePolicy) -> Game {
+        let mut game = Game::new();
+        game.set_verbose(false);
+        game.set_illegal_move_policy(policy);
+        game.register_player(ScriptedPlayer::new(
+            "Yasmin",
+            BoardToken::Cross,
+            &[
+                BoardLocation::MiddleCentre,
+                BoardLocation::TopLeft,
+                BoardLocation::TopCentre,
+                BoardLocation::BottomCentre,
+            ],
+        ))
+        .unwrap();
+        game.register_player(ScriptedPlayer::new(
+            "Mummy",
+            BoardToken::Nought,
+            &[
+                BoardLocation::MiddleCentre,
+                BoardLocation::TopLeft,
+                BoardLocation::BottomLeft,
+                BoardLocation::MiddleLeft,
+            ],
+        ))
+        .unwrap();
+        game
+    }
+
+    #[test]
+    pub fn test_illegal_move_forfeits() {
+        let mut game = occupied_cell_game(MovePolicy::Forfeit);
+        let result = game.play().unwrap();
+        assert_eq!(
+            GameResult::Player1(Win {
+                token: BoardToken::Cross,
+                lines: vec![],
+                forfeit: true,
+            }),
+            result
+        );
+        assert_eq!(1, game.history().len());
+    }
+
+    #[test]
+    pub fn test_forfeit_is_saved_and_resumed() {
+        let mut game = occupied_cell_game(MovePolicy::Forfeit);
+        let result = game.play().unwrap();
+        let path = std::env::temp_dir().join(format!("xors-forfeit-{}.txt", std::process::id()));
+        game.record().save(&path).unwrap();
+        let record = GameRecord::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(Some(Turn::Player2), record.forfeit);
+        assert_eq!(Some(result.clone()), record.result().unwrap());
+
+        let mut resumed = occupied_cell_game(MovePolicy::Forfeit);
+        resumed.resume(&record).unwrap();
+        assert_eq!(Some(result), resumed.result());
+        assert_eq!(Err(GameError::GameOver), resumed.play());
+    }
+
+    #[test]
+    pub fn test_illegal_move_retries() {
+        let mut game = occupied_cell_game(MovePolicy::Retry(1));
+        let result = game.play().unwrap();
+        // Mummy retries onto TopLeft, then Yasmin has to retry onto TopCentre.
+        assert_eq!(
+            Some(BoardToken::Nought),
+            game.board().get(BoardLocation::TopLeft)
+        );
+        assert_eq!(
+            Some(BoardToken::Cross),
+            game.board().get(BoardLocation::TopCentre)
+        );
+        assert!(matches!(
+            result,
+            GameResult::Player1(Win { forfeit: false, .. })
+        ));
+
+        let mut game = occupied_cell_game(MovePolicy::Retry(0));
+        assert!(matches!(
+            game.play().unwrap(),
+            GameResult::Player1(Win { forfeit: true, .. })
+        ));
+    }
+
+    #[test]
+    pub fn test_illegal_move_skips() {
+        let mut game = occupied_cell_game(MovePolicy::Skip);
+        let result = game.play().unwrap();
+        // Mummy loses two turns, so Yasmin plays three moves in a row.
+        let players: Vec<Turn> = game.history().iter().map(|m| m.player).collect();
+        assert_eq!(
+            vec![
+                Turn::Player1,
+                Turn::Player1,
+                Turn::Player1,
+                Turn::Player2,
+                Turn::Player1
+            ],
+            players
+        );
+        assert!(matches!(
+            result,
+            GameResult::Player1(Win { forfeit: false, .. })
+        ));
+        let record = game.record();
+        assert_eq!(7, record.moves.len());
+        assert_eq!(None, record.moves[1]);
+        assert_eq!(None, record.moves[3]);
+    }
+
+    #[test]
+    pub fn test_exhausted_player() {
+        let mut game = Game::new();
+        game.set_verbose(false);
+        game.register_player(ScriptedPlayer::new(
+            "Yasmin",
+            BoardToken::Cross,
+            &[BoardLocation::MiddleCentre],
+        ))
+        .unwrap();
+        game.register_player(ScriptedPlayer::new("Mummy", BoardToken::Nought, &[]))
+            .unwrap();
+        assert!(matches!(
+            game.play().unwrap(),
+            GameResult::Player1(Win { forfeit: true, .. })
+        ));
+
+        let mut game = Game::new();
+        game.set_verbose(false);
+        game.set_exhausted_policy(MovePolicy::Skip);
+        game.register_player(ScriptedPlayer::new(
+            "Yasmin",
+            BoardToken::Cross,
+            &[BoardLocation::MiddleCentre],
+        ))
+        .unwrap();
+        game.register_player(ScriptedPlayer::new("Mummy", BoardToken::Nought, &[]))
+            .unwrap();
+        assert_eq!(Err(GameError::Stalled), game.play());
+    }
+
+    #[test]
+    pub fn test_missing_player() {
+        let mut game = Game::new();
+        game.register_player(ScriptedPlayer::new("Yasmin", BoardToken::Cross, &[]))
+            .unwrap();
+        assert_eq!(
+            Err(GameError::PlayerNotRegistered(Turn::Player2)),
+            game.play()
+        );
+        assert_eq!(
+            "player 2 has not been registered",
+            game.play().unwrap_err().to_string()
+        );
+    }
+
+    // Claims to play noughts but puts crosses on the board.
+    struct ImpostorPlayer;
+
+    impl Player for ImpostorPlayer {
+        fn id(&self) -> &str {
+            "impostor"
+        }
+
+        fn token(&self) -> BoardToken {
+            BoardToken::Nought
+        }
+
+        fn play(&mut self, board: &GameBoard) -> Result<(BoardToken, BoardLocation), PlayerError> {
+            let location = board
+                .empty_locations()
+                .pop()
+                .ok_or(PlayerError::NoMoreMoves)?;
+            Ok((BoardToken::Cross, location))
+        }
+    }
+
+    // Never answers with a move.
+    struct ConfusedPlayer;
+
+    impl Player for ConfusedPlayer {
+        fn id(&self) -> &str {
+            "confused"
+        }
+
+        fn token(&self) -> BoardToken {
+            BoardToken::Nought
+        }
+
+        fn play(&mut self, _board: &GameBoard) -> Result<(BoardToken, BoardLocation), PlayerError> {
+            Err(PlayerError::InvalidLocation)
+        }
+    }
+
+    #[test]
+    pub fn test_invalid_moves_follow_policy() {
+        let mut game = Game::new();
+        game.set_verbose(false);
+        game.register_player(ScriptedPlayer::new(
+            "Yasmin",
+            BoardToken::Cross,
+            &[BoardLocation::MiddleCentre],
+        ))
+        .unwrap();
+        game.register_player(ConfusedPlayer).unwrap();
+        // The default retries run out rather than asking forever.
+        assert_eq!(
+            GameResult::Player1(Win {
+                token: BoardToken::Cross,
+                lines: vec![],
+                forfeit: true,
+            }),
+            game.play().unwrap()
+        );
+        assert_eq!(1, game.history().len());
+    }
+
+    #[test]
+    pub fn test_registration_rules() {
+        let mut game = Game::new();
+        game.register_player(ScriptedPlayer::new("Yasmin", BoardToken::Cross, &[]))
+            .unwrap();
+        assert_eq!(
+            Err(GameError::DuplicateToken(BoardToken::Cross)),
+            game.register_player(ScriptedPlayer::new("Mummy", BoardToken::Cross, &[]))
+        );
+        game.register_player(ScriptedPlayer::new("Mummy", BoardToken::Nought, &[]))
+            .unwrap();
+        assert_eq!(
+            Err(GameError::TooManyPlayers),
+            game.register_player(ScriptedPlayer::new("Daddy", BoardToken::Nought, &[]))
+        );
+        assert_eq!("Mummy", game.player2().id());
+    }
+
+    #[test]
+    pub fn test_wrong_token() {
+        let mut game = Game::new();
+        game.set_verbose(false);
+        game.register_player(ScriptedPlayer::new(
+            "Yasmin",
+            BoardToken::Cross,
+            &[BoardLocation::MiddleCentre],
+        ))
+        .unwrap();
+        game.register_player(ImpostorPlayer).unwrap();
+        assert_eq!(
+            Err(GameError::WrongToken {
+                turn: Turn::Player2,
+                expected: BoardToken::Nought,
+                played: BoardToken::Cross
+            }),
+            game.play()
+        );
+        assert_eq!(1, game.history().len());
+    }
+
+    #[test]
+    pub fn test_no_play_after_game_over() {
+        let mut game = Game::new();
+        game.set_verbose(false);
+        game.register_player(MinimaxPlayer::new("minimax 1", BoardToken::Cross))
+            .unwrap();
+        game.register_player(MinimaxPlayer::new("minimax 2", BoardToken::Nought))
+            .unwrap();
+        assert_eq!(GameResult::Tie, game.play().unwrap());
+        assert_eq!(Err(GameError::GameOver), game.play());
+    }
+
+    #[test]
+    pub fn test_observers() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut game = occupied_cell_game(MovePolicy::Forfeit);
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let log = Rc::clone(&events);
+        game.add_observer(move |event: &GameEvent| log.borrow_mut().push(event.clone()));
+        let moves = Rc::new(RefCell::new(0));
+        let counter = Rc::clone(&moves);
+        game.add_observer(move |event: &GameEvent| {
+            if let GameEvent::MovePlayed { .. } = event {
+                *counter.borrow_mut() += 1;
+            }
+        });
+        game.play().unwrap();
+
+        let events = events.borrow();
+        assert_eq!(7, events.len());
+        assert!(matches!(
+            &events[0],
+            GameEvent::GameStarted { player1, .. } if player1.id == "Yasmin"
+        ));
+        assert!(matches!(
+            events[1],
+            GameEvent::TurnStarted {
+                turn: Turn::Player1,
+                ..
+            }
+        ));
+        assert!(matches!(
+            &events[2],
+            GameEvent::MovePlayed { played, board }
+                if played.location == BoardLocation::MiddleCentre
+                    && board.get(BoardLocation::MiddleCentre) == Some(BoardToken::Cross)
+        ));
+        assert_eq!(
+            GameEvent::IllegalMove {
+                turn: Turn::Player2,
+                location: BoardLocation::MiddleCentre
+            },
+            events[4]
+        );
+        assert_eq!(
+            GameEvent::Forfeit {
+                turn: Turn::Player2
+            },
+            events[5]
+        );
+        assert!(matches!(
+            events[6],
+            GameEvent::GameOver(GameResult::Player1(Win { forfeit: true, .. }))
+        ));
+        assert_eq!(1, *moves.borrow());
+    }
+
+    #[test]
+    pub fn test_skip_events() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut game = Game::new();
+        game.set_verbose(false);
+        game.set_exhausted_policy(MovePolicy::Skip);
+        game.register_player(ScriptedPlayer::new(
+            "Yasmin",
+            BoardToken::Cross,
+            &[BoardLocation::MiddleCentre],
+        ))
+        .unwrap();
+        game.register_player(ScriptedPlayer::new("Mummy", BoardToken::Nought, &[]))
+            .unwrap();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let log = Rc::clone(&events);
+        game.add_observer(move |event: &GameEvent| match event {
+            GameEvent::OutOfMoves { .. } | GameEvent::TurnSkipped { .. } => {
+                log.borrow_mut().push(event.clone())
+            }
+            _ => {}
+        });
+        assert_eq!(Err(GameError::Stalled), game.play());
+        assert_eq!(
+            vec![
+                GameEvent::OutOfMoves {
+                    turn: Turn::Player2
+                },
+                GameEvent::TurnSkipped {
+                    turn: Turn::Player2
+                },
+                GameEvent::OutOfMoves {
+                    turn: Turn::Player1
+                },
+                GameEvent::TurnSkipped {
+                    turn: Turn::Player1
+                },
+            ],
+            *events.borrow()
+        );
+    }
 }
@@ -0,0 +1,698 @@
+use crate::board::*;
+use crate::player::{Player, PlayerError};
+use crate::rules::RuleSet;
+use crate::search::SYMMETRIES;
+use crate::Turn;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::fmt;
+use std::ops::Mul;
+use std::str::FromStr;
+
+// Tic-tac-toe on one or more boards where both players play crosses. A board
+// with three in a row is dead and can't be played on, and whoever kills the
+// last live board loses. Player1 moves first.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Notakto {
+    boards: Vec<GameBoard>,
+    to_move: Turn,
+}
+
+// A cell on one of the boards, with boards counted from zero. Shown and
+// parsed as the board number followed by the cell number, so 25 is the
+// centre of the second board. A lone cell number is on the first board.
+#[derive(Debug, PartialEq, Clone)]
+pub struct NotaktoMove {
+    pub board: usize,
+    pub location: BoardLocation,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum NotaktoError {
+    BadBoardCount(usize),
+    NoSuchBoard(usize),
+    DeadBoard(usize),
+    Occupied(NotaktoMove),
+    GameOver,
+    BadMove(String),
+}
+
+// An element of the misère quotient of Notakto found by Plambeck and
+// Whitehead: the commutative monoid generated by a, b, c and d with
+//   a² = 1, b³ = b, b²c = c, c³ = ac², b²d = d, cd = ad, d² = c².
+// Every board has a value in it, and a position's value is the product of
+// its boards' values. The player who just moved can force a win exactly when
+// that product is a, b², bc or c². Stored as powers of the generators, always
+// reduced.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Quotient {
+    a: u8,
+    b: u8,
+    c: u8,
+    d: u8,
+}
+
+// Plays to leave its opponent a losing position according to the quotient,
+// and otherwise plays at random while trying not to kill a board.
+pub struct NotaktoAi {
+    pub id: String,
+    pub token: Turn,
+    rng: StdRng,
+}
+
+impl Quotient {
+    pub const ONE: Quotient = Quotient::new(0, 0, 0, 0);
+
+    const fn new(a: u8, b: u8, c: u8, d: u8) -> Self {
+        Self { a, b, c, d }
+    }
+
+    fn reduce(mut self) -> Self {
+        loop {
+            if self.a >= 2 {
+                self.a -= 2;
+            } else if self.b >= 3 {
+                self.b -= 2;
+            } else if self.c >= 3 {
+                self.c -= 1;
+                self.a += 1;
+            } else if self.d >= 2 {
+                self.d -= 2;
+                self.c += 2;
+            } else if self.c >= 1 && self.d >= 1 {
+                self.c -= 1;
+                self.a += 1;
+            } else if self.b >= 2 && (self.c >= 1 || self.d >= 1) {
+                self.b -= 2;
+            } else {
+                return self;
+            }
+        }
+    }
+
+    // Whether the player who made this position can force a win from it.
+    pub fn is_previous_player_win(&self) -> bool {
+        [
+            Quotient::new(1, 0, 0, 0),
+            Quotient::new(0, 2, 0, 0),
+            Quotient::new(0, 1, 1, 0),
+            Quotient::new(0, 0, 2, 0),
+        ]
+        .contains(self)
+    }
+
+    // The value of a single board. Dead boards have no moves left and are
+    // worth 1.
+    pub fn of_board(board: &GameBoard) -> Quotient {
+        if board.winner().is_some() {
+            return Quotient::ONE;
+        }
+        symmetric_masks(board)
+            .find_map(|mask| {
+                BOARD_VALUES
+                    .iter()
+                    .find(|(board, _)| *board == mask)
+                    .map(|(_, value)| *value)
+            })
+            .expect("every live board is in the table")
+    }
+}
+
+// The board's crosses as a mask of location indices, seen under each of the
+// board's symmetries in turn.
+fn symmetric_masks(board: &GameBoard) -> impl Iterator<Item = u16> {
+    let crosses = board
+        .cells()
+        .filter(|(_, token)| token.is_some())
+        .fold(0u16, |mask, (location, _)| mask | 1 << location.index());
+    SYMMETRIES.iter().map(move |symmetry| {
+        (0..9).fold(0, |mask, cell| {
+            mask | (crosses >> symmetry[cell] & 1) << cell
+        })
+    })
+}
+
+impl Mul for Quotient {
+    type Output = Quotient;
+
+    fn mul(self, other: Quotient) -> Quotient {
+        Quotient::new(
+            self.a + other.a,
+            self.b + other.b,
+            self.c + other.c,
+            self.d + other.d,
+        )
+        .reduce()
+    }
+}
+
+const ONE: Quotient = Quotient::ONE;
+const A: Quotient = Quotient::new(1, 0, 0, 0);
+const B: Quotient = Quotient::new(0, 1, 0, 0);
+const AB: Quotient = Quotient::new(1, 1, 0, 0);
+const C: Quotient = Quotient::new(0, 0, 1, 0);
+const C2: Quotient = Quotient::new(0, 0, 2, 0);
+const D: Quotient = Quotient::new(0, 0, 0, 1);
+const AD: Quotient = Quotient::new(1, 0, 0, 1);
+
+// The value of every live board up to symmetry. Bit n is set when there is a
+// cross at the location with index n, so the top row is the last three digits.
+const BOARD_VALUES: [(u16, Quotient); 46] = [
+    (0b000_000_000, C),
+    (0b000_000_001, ONE),
+    (0b000_000_010, ONE),
+    (0b000_000_011, AD),
+    (0b000_000_101, B),
+    (0b000_001_010, A),
+    (0b000_001_011, B),
+    (0b000_001_100, B),
+    (0b000_001_101, A),
+    (0b000_001_110, D),
+    (0b000_010_000, C2),
+    (0b000_010_001, B),
+    (0b000_010_010, B),
+    (0b000_010_011, AB),
+    (0b000_010_101, A),
+    (0b000_011_010, AB),
+    (0b000_011_011, A),
+    (0b000_011_100, A),
+    (0b000_011_101, B),
+    (0b000_011_110, B),
+    (0b000_101_000, A),
+    (0b000_101_001, D),
+    (0b000_101_010, B),
+    (0b000_101_011, A),
+    (0b000_101_101, B),
+    (0b001_000_100, A),
+    (0b001_000_101, AB),
+    (0b001_000_110, D),
+    (0b001_001_110, AB),
+    (0b001_100_001, A),
+    (0b001_100_010, ONE),
+    (0b001_100_011, B),
+    (0b001_100_101, B),
+    (0b001_100_110, A),
+    (0b001_101_010, AB),
+    (0b001_101_100, A),
+    (0b001_101_110, B),
+    (0b001_110_001, B),
+    (0b001_110_010, B),
+    (0b001_110_011, A),
+    (0b010_101_010, A),
+    (0b010_101_011, B),
+    (0b010_101_101, A),
+    (0b011_100_101, A),
+    (0b011_101_110, A),
+    (0b101_000_101, A),
+];
+
+impl NotaktoMove {
+    pub fn new(board: usize, location: BoardLocation) -> Self {
+        Self { board, location }
+    }
+}
+
+impl Notakto {
+    pub const MAX_BOARDS: usize = 9;
+
+    pub fn new(boards: usize) -> Result<Self, NotaktoError> {
+        if boards == 0 || boards > Self::MAX_BOARDS {
+            return Err(NotaktoError::BadBoardCount(boards));
+        }
+        Ok(Self {
+            boards: vec![GameBoard::default(); boards],
+            to_move: Turn::Player1,
+        })
+    }
+
+    pub fn boards(&self) -> &[GameBoard] {
+        &self.boards
+    }
+
+    pub fn to_move(&self) -> Turn {
+        self.to_move
+    }
+
+    pub fn is_dead(&self, board: usize) -> bool {
+        self.boards[board].winner().is_some()
+    }
+
+    pub fn is_over(&self) -> bool {
+        (0..self.boards.len()).all(|board| self.is_dead(board))
+    }
+
+    pub fn legal_moves(&self) -> Vec<NotaktoMove> {
+        (0..self.boards.len())
+            .filter(|board| !self.is_dead(*board))
+            .flat_map(|board| {
+                self.boards[board]
+                    .empty_locations()
+                    .into_iter()
+                    .map(move |location| NotaktoMove::new(board, location))
+            })
+            .collect()
+    }
+
+    // The product of the boards' values.
+    pub fn value(&self) -> Quotient {
+        self.boards
+            .iter()
+            .map(Quotient::of_board)
+            .fold(Quotient::ONE, Mul::mul)
+    }
+}
+
+// Both players play crosses, so the players' tokens are their turns.
+impl Board for Notakto {
+    type Token = Turn;
+    type Move = NotaktoMove;
+    // A line on one of the boards, counted from zero.
+    type Line = (usize, BoardLine);
+    type Error = NotaktoError;
+
+    fn legal_moves(&self, _turn: &Turn) -> Vec<NotaktoMove> {
+        Notakto::legal_moves(self)
+    }
+
+    fn play_move(&mut self, turn: &Turn, mv: &NotaktoMove) -> Result<(), NotaktoError> {
+        if self.is_over() {
+            return Err(NotaktoError::GameOver);
+        }
+        if mv.board >= self.boards.len() {
+            return Err(NotaktoError::NoSuchBoard(mv.board));
+        }
+        if self.is_dead(mv.board) {
+            return Err(NotaktoError::DeadBoard(mv.board));
+        }
+        self.boards[mv.board]
+            .place(mv.location.clone(), BoardToken::Cross)
+            .map_err(|_| NotaktoError::Occupied(mv.clone()))?;
+        self.to_move = turn.other();
+        Ok(())
+    }
+
+    // The player who killed the last board has lost, and it is now the other
+    // player's turn. Notakto is a misère game already, so the rules make no
+    // difference. Every dead board's lines are given.
+    fn won_by(&self, _rules: RuleSet) -> Option<(Turn, Vec<(usize, BoardLine)>)> {
+        if !self.is_over() {
+            return None;
+        }
+        let lines = self
+            .boards
+            .iter()
+            .enumerate()
+            .flat_map(|(index, board)| {
+                board
+                    .winning_lines()
+                    .into_iter()
+                    .map(move |line| (index, line))
+            })
+            .collect();
+        Some((self.to_move, lines))
+    }
+
+    fn is_finished(&self) -> bool {
+        self.is_over()
+    }
+
+    fn move_prompt(&self, _turn: &Turn) -> String {
+        match self.boards.len() {
+            1 => "Enter a cell (1-9)".into(),
+            boards => format!("Enter a board and cell (11-{}9)", boards),
+        }
+    }
+}
+
+impl NotaktoAi {
+    pub fn new(id: &str, token: Turn) -> Self {
+        Self {
+            id: String::from(id),
+            token,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    // Makes the choice between equally good moves repeatable.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+}
+
+impl Player<Notakto> for NotaktoAi {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn token(&self) -> Turn {
+        self.token
+    }
+
+    fn play(&mut self, state: &Notakto) -> Result<(Turn, NotaktoMove), PlayerError> {
+        let moves = state.legal_moves();
+        let after = |mv: &NotaktoMove| {
+            let mut next = state.clone();
+            next.play_move(&next.to_move(), mv)
+                .expect("the move is legal");
+            next
+        };
+        let winning: Vec<&NotaktoMove> = moves
+            .iter()
+            .filter(|mv| after(mv).value().is_previous_player_win())
+            .collect();
+        let quiet: Vec<&NotaktoMove> = moves
+            .iter()
+            .filter(|mv| !after(mv).is_dead(mv.board))
+            .collect();
+        let candidates = if !winning.is_empty() {
+            winning
+        } else if !quiet.is_empty() {
+            quiet
+        } else {
+            moves.iter().collect()
+        };
+        candidates
+            .choose(&mut self.rng)
+            .map(|mv| (self.token, (*mv).clone()))
+            .ok_or(PlayerError::NoMoreMoves)
+    }
+}
+
+impl fmt::Display for Quotient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self == Quotient::ONE {
+            return write!(f, "1");
+        }
+        for (generator, power) in [('a', self.a), ('b', self.b), ('c', self.c), ('d', self.d)] {
+            match power {
+                0 => {}
+                1 => write!(f, "{}", generator)?,
+                _ => write!(f, "{}²", generator)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for NotaktoMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.board + 1, self.location.number())
+    }
+}
+
+impl FromStr for NotaktoMove {
+    type Err = NotaktoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let bad = || NotaktoError::BadMove(s.into());
+        let digits: Vec<u32> = s
+            .chars()
+            .filter(|c| !matches!(c, ' ' | ','))
+            .map(|c| c.to_digit(10).ok_or_else(bad))
+            .collect::<Result<_, _>>()?;
+        let (board, cell) = match digits[..] {
+            [cell] => (1, cell),
+            [board, cell] if board > 0 => (board, cell),
+            _ => return Err(bad()),
+        };
+        let location = BoardLocation::from_number(cell).ok_or_else(bad)?;
+        Ok(NotaktoMove::new(board as usize - 1, location))
+    }
+}
+
+impl fmt::Display for NotaktoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotaktoError::BadBoardCount(boards) => write!(
+                f,
+                "can't play on {} boards, it has to be 1 to {}",
+                boards,
+                Notakto::MAX_BOARDS
+            ),
+            NotaktoError::NoSuchBoard(board) => write!(f, "there is no board {}", board + 1),
+            NotaktoError::DeadBoard(board) => write!(f, "board {} is dead", board + 1),
+            NotaktoError::Occupied(mv) => write!(
+                f,
+                "{} on board {} is already occupied",
+                mv.location,
+                mv.board + 1
+            ),
+            NotaktoError::GameOver => write!(f, "the game is already over"),
+            NotaktoError::BadMove(s) => write!(f, "{:?} is not a board and cell such as 25", s),
+        }
+    }
+}
+
+impl std::error::Error for NotaktoError {}
+
+// The boards side by side, each headed by its number.
+impl fmt::Display for Notakto {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header: Vec<String> = (0..self.boards.len())
+            .map(|board| {
+                if self.is_dead(board) {
+                    format!("{} dead", board + 1)
+                } else {
+                    format!("{}", board + 1)
+                }
+            })
+            .collect();
+        writeln!(f, "{}", columns(&header))?;
+        for row in 0..3 {
+            let cells: Vec<String> = self
+                .boards
+                .iter()
+                .map(|board| {
+                    BoardLocation::ALL[row * 3..row * 3 + 3]
+                        .iter()
+                        .map(|location| match board.get(location.clone()) {
+                            Some(token) => token.to_string(),
+                            None => ".".into(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect();
+            writeln!(f, "{}", columns(&cells))?;
+        }
+        Ok(())
+    }
+}
+
+fn columns(texts: &[String]) -> String {
+    texts
+        .iter()
+        .map(|text| format!("{:<8}", text))
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::RandomPlayer;
+    use crate::Game;
+    use std::collections::HashMap;
+
+    fn moves(names: &[&str]) -> Vec<NotaktoMove> {
+        names.iter().map(|name| name.parse().unwrap()).collect()
+    }
+
+    fn play(boards: usize, names: &[&str]) -> Notakto {
+        let mut game = Notakto::new(boards).unwrap();
+        for mv in moves(names) {
+            game.play_move(&game.to_move(), &mv).unwrap();
+        }
+        game
+    }
+
+    // The live boards as crosses masks, each turned to its smallest
+    // symmetric form and then sorted, so equivalent positions match.
+    fn key(state: &Notakto) -> Vec<u16> {
+        let mut key: Vec<u16> = (0..state.boards().len())
+            .filter(|board| !state.is_dead(*board))
+            .map(|board| symmetric_masks(&state.boards()[board]).min().unwrap())
+            .collect();
+        key.sort();
+        key
+    }
+
+    // Whether the player to move loses with best play, found by searching
+    // every line.
+    fn is_lost(state: &Notakto, known: &mut HashMap<Vec<u16>, bool>) -> bool {
+        if state.is_over() {
+            return false;
+        }
+        let key = key(state);
+        if let Some(lost) = known.get(&key) {
+            return *lost;
+        }
+        let lost = state.legal_moves().iter().all(|mv| {
+            let mut next = state.clone();
+            next.play_move(&next.to_move(), mv).unwrap();
+            !is_lost(&next, known)
+        });
+        known.insert(key, lost);
+        lost
+    }
+
+    #[test]
+    pub fn test_quotient_arithmetic() {
+        assert_eq!(ONE, A * A);
+        assert_eq!(B, B * B * B);
+        assert_eq!(C, B * B * C);
+        assert_eq!(A * C2, C * C * C);
+        assert_eq!(AD, C * D);
+        assert_eq!(C2, D * D);
+        assert_eq!("ac²", (C * C * C).to_string());
+        assert_eq!("1", ONE.to_string());
+        assert!((B * C).is_previous_player_win());
+        assert!(!C.is_previous_player_win());
+    }
+
+    #[test]
+    pub fn test_board_values() {
+        let empty = GameBoard::default();
+        assert_eq!(C, Quotient::of_board(&empty));
+        let mut centre = empty.clone();
+        centre
+            .play(BoardLocation::MiddleCentre, BoardToken::Cross)
+            .unwrap();
+        assert_eq!(C2, Quotient::of_board(&centre));
+        // Any corner is the same as the top left.
+        let mut corner = empty.clone();
+        corner
+            .play(BoardLocation::BottomRight, BoardToken::Cross)
+            .unwrap();
+        assert_eq!(ONE, Quotient::of_board(&corner));
+    }
+
+    #[test]
+    pub fn test_quotient_agrees_with_search() {
+        // Every position a few moves into games on two and three boards.
+        let mut known = HashMap::new();
+        for (boards, depth) in [(2, 3), (3, 2)] {
+            let mut positions = vec![Notakto::new(boards).unwrap()];
+            for _ in 0..depth {
+                positions = positions
+                    .iter()
+                    .flat_map(|state| {
+                        state.legal_moves().into_iter().map(move |mv| {
+                            let mut next = state.clone();
+                            next.play_move(&next.to_move(), &mv).unwrap();
+                            next
+                        })
+                    })
+                    .collect();
+                positions.sort_by_key(key);
+                positions.dedup_by_key(|state| key(state));
+                for state in &positions {
+                    assert_eq!(
+                        is_lost(state, &mut known),
+                        state.value().is_previous_player_win(),
+                        "{}",
+                        state
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_dead_boards() {
+        let mut game = play(2, &["11", "12"]);
+        assert!(!game.is_dead(0));
+        game.play_move(&game.to_move(), &"13".parse().unwrap())
+            .unwrap();
+        assert!(game.is_dead(0));
+        assert!(!game.is_over());
+        assert_eq!(
+            Err(NotaktoError::DeadBoard(0)),
+            game.play_move(&game.to_move(), &"15".parse().unwrap())
+        );
+        assert_eq!(
+            Err(NotaktoError::NoSuchBoard(2)),
+            game.play_move(&game.to_move(), &"35".parse().unwrap())
+        );
+        assert!(game.legal_moves().iter().all(|mv| mv.board == 1));
+    }
+
+    #[test]
+    pub fn test_killing_the_last_board_loses() {
+        let game = play(1, &["1", "2"]);
+        assert_eq!(None, game.won_by(RuleSet::Standard));
+        let game = play(1, &["1", "2", "3"]);
+        // Player1 made the line, so player2 wins.
+        assert_eq!(
+            Some((Turn::Player2, vec![(0, BoardLine::TopRow)])),
+            game.won_by(RuleSet::Standard)
+        );
+        assert!(game.legal_moves().is_empty());
+    }
+
+    #[test]
+    pub fn test_move_names() {
+        assert_eq!(
+            Ok(NotaktoMove::new(1, BoardLocation::MiddleCentre)),
+            "25".parse()
+        );
+        assert_eq!(
+            Ok(NotaktoMove::new(1, BoardLocation::MiddleCentre)),
+            "2 5".parse()
+        );
+        assert_eq!(Ok(NotaktoMove::new(0, BoardLocation::TopLeft)), "1".parse());
+        assert_eq!(
+            "25",
+            NotaktoMove::new(1, BoardLocation::MiddleCentre).to_string()
+        );
+        assert!("05".parse::<NotaktoMove>().is_err());
+        assert!("20".parse::<NotaktoMove>().is_err());
+        assert!("x".parse::<NotaktoMove>().is_err());
+        assert!(Notakto::new(0).is_err());
+        assert!(Notakto::new(10).is_err());
+    }
+
+    #[test]
+    pub fn test_display() {
+        let game = play(2, &["11", "12", "13", "25"]);
+        assert_eq!(
+            "1 dead  2\nX X X   . . .\n. . .   . X .\n. . .   . . .\n",
+            game.to_string()
+        );
+    }
+
+    #[test]
+    pub fn test_ai_beats_a_random_player() {
+        // Two empty boards are worth c², so the second player wins there. On
+        // one or three boards it's the first.
+        for boards in 1..=3 {
+            let state = Notakto::new(boards).unwrap();
+            let ai_first = !state.value().is_previous_player_win();
+            for seed in 0..3 {
+                let mut game = Game::new_with_board(state.clone());
+                game.set_verbose(false);
+                let (ai_turn, random_turn) = if ai_first {
+                    (Turn::Player1, Turn::Player2)
+                } else {
+                    (Turn::Player2, Turn::Player1)
+                };
+                let mut ai = NotaktoAi::new("ai", ai_turn);
+                ai.set_seed(seed);
+                let mut random = RandomPlayer::new("random", random_turn);
+                random.set_seed(seed);
+                if ai_first {
+                    game.register_player(ai).unwrap();
+                    game.register_player(random).unwrap();
+                } else {
+                    game.register_player(random).unwrap();
+                    game.register_player(ai).unwrap();
+                }
+                let result = game.play().unwrap();
+                assert_eq!(Some(ai_turn), result.win().map(|win| win.token));
+            }
+        }
+    }
+}
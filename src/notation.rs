@@ -0,0 +1,260 @@
+use crate::board::*;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Position {
+    pub board: GameBoard,
+    pub to_move: BoardToken,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum NotationError {
+    BadLength(String),
+    BadCharacter(char),
+    ImpossibleTokenCounts { crosses: usize, noughts: usize },
+    BadSideToMove(String),
+    WrongSideToMove(BoardToken),
+    BothWin,
+    WinnerNotLast(BoardToken),
+    WinnerToMove(BoardToken),
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotationError::BadLength(s) => {
+                write!(f, "{:?} is not three rows of three cells", s)
+            }
+            NotationError::BadCharacter(c) => {
+                write!(f, "{:?} is not one of X, O or .", c)
+            }
+            NotationError::ImpossibleTokenCounts { crosses, noughts } => write!(
+                f,
+                "{} crosses and {} noughts can't come from alternating play",
+                crosses, noughts
+            ),
+            NotationError::BadSideToMove(side) => {
+                write!(f, "{:?} is not a side to move, expected x or o", side)
+            }
+            NotationError::WrongSideToMove(token) => write!(
+                f,
+                "{} can't be to move when it has more tokens on the board",
+                token
+            ),
+            NotationError::BothWin => write!(f, "both sides can't have a line"),
+            NotationError::WinnerNotLast(token) => write!(
+                f,
+                "{} has a line with fewer tokens, so didn't make the last move",
+                token
+            ),
+            NotationError::WinnerToMove(token) => {
+                write!(f, "{} has already won, so can't be to move", token)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NotationError {}
+
+fn token_char(token: &Option<BoardToken>) -> char {
+    match token {
+        Some(BoardToken::Cross) => 'X',
+        Some(BoardToken::Nought) => 'O',
+        None => '.',
+    }
+}
+
+fn parse_side(side: &str) -> Result<BoardToken, NotationError> {
+    match side {
+        "x" | "X" => Ok(BoardToken::Cross),
+        "o" | "O" => Ok(BoardToken::Nought),
+        _ => Err(NotationError::BadSideToMove(side.into())),
+    }
+}
+
+impl GameBoard {
+    pub fn notation(&self) -> String {
+        let cells: Vec<char> = self.cells().map(|(_, token)| token_char(&token)).collect();
+        cells
+            .chunks(3)
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<String>>()
+            .join("/")
+    }
+}
+
+impl FromStr for GameBoard {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<&str> = s.trim().split('/').collect();
+        if rows.len() != 3 || rows.iter().any(|row| row.chars().count() != 3) {
+            return Err(NotationError::BadLength(s.into()));
+        }
+        let mut board = GameBoard::default();
+        for (location, c) in BoardLocation::ALL.into_iter().zip(rows.concat().chars()) {
+            let token = match c {
+                'X' | 'x' => BoardToken::Cross,
+                'O' | 'o' => BoardToken::Nought,
+                '.' => continue,
+                _ => return Err(NotationError::BadCharacter(c)),
+            };
+            board
+                .place(location, token)
+                .expect("each location is only visited once");
+        }
+        let crosses = board.token_count(&BoardToken::Cross);
+        let noughts = board.token_count(&BoardToken::Nought);
+        if crosses.abs_diff(noughts) > 1 {
+            return Err(NotationError::ImpossibleTokenCounts { crosses, noughts });
+        }
+        // The game stops at the first line, so only the side that made the
+        // last move can have one.
+        let lines = board.winning_lines();
+        let mut winners = lines
+            .iter()
+            .filter_map(|line| board.get(line.locations()[0].clone()));
+        if let Some(winner) = winners.next() {
+            if winners.any(|token| token != winner) {
+                return Err(NotationError::BothWin);
+            }
+            if board.token_count(&winner) < board.token_count(&winner.opponent()) {
+                return Err(NotationError::WinnerNotLast(winner));
+            }
+        }
+        Ok(board)
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = match self.to_move {
+            BoardToken::Cross => 'x',
+            BoardToken::Nought => 'o',
+        };
+        write!(f, "{} {}", self.board.notation(), side)
+    }
+}
+
+impl FromStr for Position {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let board: GameBoard = parts.next().unwrap_or_default().parse()?;
+        let to_move = match (parts.next(), parts.next()) {
+            (Some(side), None) => parse_side(side)?,
+            (None, _) => return Err(NotationError::BadSideToMove(String::new())),
+            (Some(_), Some(extra)) => return Err(NotationError::BadSideToMove(extra.into())),
+        };
+        // When one side has an extra token the other side must be to move.
+        let crosses = board.token_count(&BoardToken::Cross);
+        let noughts = board.token_count(&BoardToken::Nought);
+        if (crosses > noughts && to_move == BoardToken::Cross)
+            || (noughts > crosses && to_move == BoardToken::Nought)
+        {
+            return Err(NotationError::WrongSideToMove(to_move));
+        }
+        if board.winner().is_some_and(|(winner, _)| winner == to_move) {
+            return Err(NotationError::WinnerToMove(to_move));
+        }
+        Ok(Position { board, to_move })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_parse_board() {
+        let board: GameBoard = "X.O/.X./..O".parse().unwrap();
+        assert_eq!(Some(BoardToken::Cross), board.get(BoardLocation::TopLeft));
+        assert_eq!(None, board.get(BoardLocation::TopCentre));
+        assert_eq!(Some(BoardToken::Nought), board.get(BoardLocation::TopRight));
+        assert_eq!(
+            Some(BoardToken::Cross),
+            board.get(BoardLocation::MiddleCentre)
+        );
+        assert_eq!(
+            Some(BoardToken::Nought),
+            board.get(BoardLocation::BottomRight)
+        );
+        assert_eq!(5, board.empty_locations().len());
+    }
+
+    #[test]
+    pub fn test_notation_round_trip() {
+        let position: Position = "X.O/.X./..O x".parse().unwrap();
+        assert_eq!(BoardToken::Cross, position.to_move);
+        assert_eq!("X.O/.X./..O x", position.to_string());
+        assert_eq!(".../.../...", GameBoard::default().notation());
+    }
+
+    #[test]
+    pub fn test_bad_length() {
+        assert!(matches!(
+            "X.O/.X.".parse::<GameBoard>(),
+            Err(NotationError::BadLength(_))
+        ));
+        assert!(matches!(
+            "X.O/.X../..O".parse::<GameBoard>(),
+            Err(NotationError::BadLength(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_bad_character() {
+        assert_eq!(
+            Err(NotationError::BadCharacter('Z')),
+            "X.O/.Z./..O".parse::<GameBoard>()
+        );
+    }
+
+    #[test]
+    pub fn test_impossible_token_counts() {
+        assert_eq!(
+            Err(NotationError::ImpossibleTokenCounts {
+                crosses: 3,
+                noughts: 1
+            }),
+            "XXX/.O./...".parse::<GameBoard>()
+        );
+    }
+
+    #[test]
+    pub fn test_bad_side_to_move() {
+        assert_eq!(
+            Err(NotationError::BadSideToMove("q".into())),
+            "X.O/.X./..O q".parse::<Position>()
+        );
+        assert_eq!(
+            Err(NotationError::BadSideToMove(String::new())),
+            "X.O/.X./..O".parse::<Position>()
+        );
+        assert_eq!(
+            Err(NotationError::WrongSideToMove(BoardToken::Cross)),
+            "X../.../... x".parse::<Position>()
+        );
+        assert_eq!(
+            Err(NotationError::WinnerToMove(BoardToken::Cross)),
+            "XXX/OO./O.. x".parse::<Position>()
+        );
+    }
+
+    #[test]
+    pub fn test_impossible_wins() {
+        assert_eq!(
+            Err(NotationError::BothWin),
+            "XXX/OOO/...".parse::<GameBoard>()
+        );
+        assert_eq!(
+            Err(NotationError::WinnerNotLast(BoardToken::Nought)),
+            "XX./OOO/XX.".parse::<GameBoard>()
+        );
+        // Noughts may have opened, so a line with as many tokens as the other
+        // side is fine.
+        assert!("XXX/OO./O..".parse::<GameBoard>().is_ok());
+    }
+}
@@ -0,0 +1,433 @@
+use crate::board::*;
+use crate::rules::RuleSet;
+use crate::Turn;
+use std::fmt;
+use std::str::FromStr;
+
+pub const TARGET: u32 = 15;
+
+// A 3x3 board whose cells hold the numbers 1 to 9, each at most once.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct NumberBoard {
+    cells: [Option<u32>; 9],
+}
+
+// Numerical tic-tac-toe: player1 places the odd numbers and player2 the even
+// ones, and whoever completes a line adding up to 15 wins. Player1 moves
+// first.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Numerical {
+    board: NumberBoard,
+    to_move: Turn,
+}
+
+// Shown and parsed as the cell followed by the number, such as "5 3".
+#[derive(Debug, PartialEq, Clone)]
+pub struct NumericalMove {
+    pub location: BoardLocation,
+    pub number: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum NumericalError {
+    NotYourNumber { number: u32, turn: Turn },
+    AlreadyPlayed(u32),
+    Occupied(BoardLocation),
+    GameOver,
+    BadMove(String),
+}
+
+impl NumberBoard {
+    pub fn get(&self, location: BoardLocation) -> Option<u32> {
+        self.cells[location.index()]
+    }
+
+    pub fn contains(&self, number: u32) -> bool {
+        self.cells.contains(&Some(number))
+    }
+
+    pub fn empty_locations(&self) -> Vec<BoardLocation> {
+        BoardLocation::ALL
+            .into_iter()
+            .filter(|location| self.get(location.clone()).is_none())
+            .collect()
+    }
+
+    pub fn play(&mut self, location: BoardLocation, number: u32) -> Result<(), NumericalError> {
+        if self.contains(number) {
+            return Err(NumericalError::AlreadyPlayed(number));
+        }
+        match &mut self.cells[location.index()] {
+            Some(_) => Err(NumericalError::Occupied(location)),
+            cell => {
+                *cell = Some(number);
+                Ok(())
+            }
+        }
+    }
+
+    // A line wins once all three of its cells are filled and add up to 15.
+    pub fn is_line_win(&self, line: &BoardLine) -> bool {
+        match line.contents(|location| self.get(location)) {
+            [Some(first), Some(second), Some(third)] => first + second + third == TARGET,
+            _ => false,
+        }
+    }
+
+    pub fn winning_lines(&self) -> Vec<BoardLine> {
+        BoardLine::ALL
+            .into_iter()
+            .filter(|line| self.is_line_win(line))
+            .collect()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.cells.iter().all(Option::is_some)
+    }
+}
+
+impl NumericalMove {
+    pub fn new(location: BoardLocation, number: u32) -> Self {
+        Self { location, number }
+    }
+}
+
+impl Default for Numerical {
+    fn default() -> Self {
+        Self {
+            board: NumberBoard::default(),
+            to_move: Turn::Player1,
+        }
+    }
+}
+
+impl Numerical {
+    pub fn board(&self) -> &NumberBoard {
+        &self.board
+    }
+
+    pub fn to_move(&self) -> Turn {
+        self.to_move
+    }
+
+    pub fn is_over(&self) -> bool {
+        !self.board.winning_lines().is_empty() || self.board.is_full()
+    }
+
+    // Player1 has the odd numbers and player2 the even ones.
+    pub fn owns(turn: Turn, number: u32) -> bool {
+        (1..=9).contains(&number) && (number % 2 == 1) == (turn == Turn::Player1)
+    }
+
+    // The numbers `turn` still has to play, smallest first.
+    pub fn numbers_left(&self, turn: Turn) -> Vec<u32> {
+        (1..=9)
+            .filter(|&number| Self::owns(turn, number) && !self.board.contains(number))
+            .collect()
+    }
+
+    pub fn legal_moves(&self) -> Vec<NumericalMove> {
+        self.legal_moves_for(self.to_move)
+    }
+
+    fn legal_moves_for(&self, turn: Turn) -> Vec<NumericalMove> {
+        if self.is_over() {
+            return Vec::new();
+        }
+        let numbers = self.numbers_left(turn);
+        self.board
+            .empty_locations()
+            .into_iter()
+            .flat_map(|location| {
+                numbers
+                    .iter()
+                    .map(move |&number| NumericalMove::new(location.clone(), number))
+            })
+            .collect()
+    }
+}
+
+// The players' tokens are their turns, which decide the numbers they play.
+impl Board for Numerical {
+    type Token = Turn;
+    type Move = NumericalMove;
+    type Line = BoardLine;
+    type Error = NumericalError;
+
+    fn legal_moves(&self, turn: &Turn) -> Vec<NumericalMove> {
+        self.legal_moves_for(*turn)
+    }
+
+    fn play_move(&mut self, turn: &Turn, mv: &NumericalMove) -> Result<(), NumericalError> {
+        if self.is_over() {
+            return Err(NumericalError::GameOver);
+        }
+        if !Self::owns(*turn, mv.number) {
+            return Err(NumericalError::NotYourNumber {
+                number: mv.number,
+                turn: *turn,
+            });
+        }
+        self.board.play(mv.location.clone(), mv.number)?;
+        self.to_move = turn.other();
+        Ok(())
+    }
+
+    // Only the last move can have completed a line, so it was made by
+    // whoever played it.
+    fn won_by(&self, rules: RuleSet) -> Option<(Turn, Vec<BoardLine>)> {
+        let lines = self.board.winning_lines();
+        if lines.is_empty() {
+            return None;
+        }
+        Some((rules.winning_turn(self.to_move.other()), lines))
+    }
+
+    fn is_finished(&self) -> bool {
+        self.board.is_full()
+    }
+
+    fn move_prompt(&self, turn: &Turn) -> String {
+        let numbers: Vec<String> = self
+            .numbers_left(*turn)
+            .iter()
+            .map(u32::to_string)
+            .collect();
+        format!(
+            "Enter a cell and a number from {} (e.g. 5 {})",
+            numbers.join(" "),
+            numbers.first().map(String::as_str).unwrap_or("1")
+        )
+    }
+}
+
+impl fmt::Display for NumericalMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.location.number(), self.number)
+    }
+}
+
+impl FromStr for NumericalMove {
+    type Err = NumericalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let bad = || NumericalError::BadMove(s.into());
+        let digits: Vec<u32> = s
+            .chars()
+            .filter(|c| !matches!(c, ' ' | ','))
+            .map(|c| c.to_digit(10).ok_or_else(bad))
+            .collect::<Result<_, _>>()?;
+        match digits[..] {
+            [cell, number @ 1..=9] => Ok(NumericalMove::new(
+                BoardLocation::from_number(cell).ok_or_else(bad)?,
+                number,
+            )),
+            _ => Err(bad()),
+        }
+    }
+}
+
+impl fmt::Display for NumericalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NumericalError::NotYourNumber { number, turn } => {
+                let parity = if *turn == Turn::Player1 {
+                    "odd"
+                } else {
+                    "even"
+                };
+                write!(f, "{} isn't yours, you play the {} numbers", number, parity)
+            }
+            NumericalError::AlreadyPlayed(number) => {
+                write!(f, "{} has already been played", number)
+            }
+            NumericalError::Occupied(location) => write!(f, "{} is already occupied", location),
+            NumericalError::GameOver => write!(f, "the game is already over"),
+            NumericalError::BadMove(s) => {
+                write!(f, "{:?} is not a cell and number such as 5 3", s)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NumericalError {}
+
+fn display_number(number: Option<u32>) -> String {
+    number.map_or(" ".into(), |number| number.to_string())
+}
+
+impl fmt::Display for NumberBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (row, locations) in BoardLocation::ALL.chunks(3).enumerate() {
+            if row > 0 {
+                writeln!(f, "-----------------")?;
+            }
+            writeln!(f, "     |     |     ")?;
+            let numbers: Vec<String> = locations
+                .iter()
+                .map(|location| format!("  {}  ", display_number(self.get(location.clone()))))
+                .collect();
+            writeln!(f, "{}", numbers.join("|"))?;
+            let labels: Vec<String> = locations
+                .iter()
+                .map(|location| format!("{:<5}", location.number()))
+                .collect();
+            writeln!(f, "{}", labels.join("|"))?;
+        }
+        Ok(())
+    }
+}
+
+// The board, then the numbers each side has left.
+impl fmt::Display for Numerical {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.board)?;
+        for (turn, parity) in [(Turn::Player1, "Odd"), (Turn::Player2, "Even")] {
+            let numbers: Vec<String> = self.numbers_left(turn).iter().map(u32::to_string).collect();
+            writeln!(f, "{} left: {}", parity, numbers.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::RandomPlayer;
+    use crate::Game;
+
+    fn winner(numerical: &Numerical) -> Option<Turn> {
+        numerical.won_by(RuleSet::Standard).map(|(turn, _)| turn)
+    }
+
+    fn play(names: &[&str]) -> Numerical {
+        let mut numerical = Numerical::default();
+        for name in names {
+            numerical
+                .play_move(&numerical.to_move(), &name.parse().unwrap())
+                .unwrap();
+        }
+        numerical
+    }
+
+    #[test]
+    pub fn test_move_names() {
+        let mv = NumericalMove::new(BoardLocation::MiddleCentre, 3);
+        assert_eq!("5 3", mv.to_string());
+        assert_eq!(Ok(mv.clone()), "5 3".parse());
+        assert_eq!(Ok(mv.clone()), "5,3".parse());
+        assert_eq!(Ok(mv), " 53\n".parse());
+        assert!("5".parse::<NumericalMove>().is_err());
+        assert!("5 0".parse::<NumericalMove>().is_err());
+        assert!("0 3".parse::<NumericalMove>().is_err());
+        assert!("5 x".parse::<NumericalMove>().is_err());
+    }
+
+    #[test]
+    pub fn test_numbers() {
+        let mut numerical = play(&["5 1", "1 8"]);
+        assert_eq!(vec![3, 5, 7, 9], numerical.numbers_left(Turn::Player1));
+        assert_eq!(vec![2, 4, 6], numerical.numbers_left(Turn::Player2));
+        assert_eq!(7 * 4, numerical.legal_moves().len());
+        assert_eq!(
+            Err(NumericalError::NotYourNumber {
+                number: 4,
+                turn: Turn::Player1
+            }),
+            numerical.play_move(&numerical.to_move(), &"2 4".parse().unwrap())
+        );
+        assert_eq!(
+            Err(NumericalError::AlreadyPlayed(1)),
+            numerical.play_move(&numerical.to_move(), &"2 1".parse().unwrap())
+        );
+        assert_eq!(
+            Err(NumericalError::Occupied(BoardLocation::MiddleCentre)),
+            numerical.play_move(&numerical.to_move(), &"5 3".parse().unwrap())
+        );
+        assert_eq!(Turn::Player1, numerical.to_move());
+    }
+
+    #[test]
+    pub fn test_the_mover_wins() {
+        let mut numerical = play(&["1 1", "5 8", "9 3", "4 6"]);
+        assert_eq!(None, winner(&numerical));
+        numerical
+            .play_move(&numerical.to_move(), &"7 5".parse().unwrap())
+            .unwrap();
+        assert_eq!(None, winner(&numerical));
+
+        // Player2 completes 8 + 1 + 6 down the left column.
+        let mut numerical = play(&["4 1", "1 8", "9 3", "7 6"]);
+        assert_eq!(
+            vec![BoardLine::LeftColumn],
+            numerical.board().winning_lines()
+        );
+        assert_eq!(Some(Turn::Player2), winner(&numerical));
+        assert!(numerical.legal_moves().is_empty());
+        assert_eq!(
+            Err(NumericalError::GameOver),
+            numerical.play_move(&numerical.to_move(), &"5 5".parse().unwrap())
+        );
+
+        // A full line that doesn't add up to 15 isn't a win.
+        let numerical = play(&["1 1", "2 2", "3 3"]);
+        assert_eq!(None, winner(&numerical));
+    }
+
+    #[test]
+    pub fn test_tie() {
+        let numerical = play(&[
+            "1 1", "2 2", "3 3", "4 4", "5 5", "7 6", "6 7", "9 8", "8 9",
+        ]);
+        assert!(numerical.board().is_full());
+        assert!(numerical.board().winning_lines().is_empty());
+        assert_eq!(None, winner(&numerical));
+        assert!(numerical.is_finished());
+    }
+
+    #[test]
+    pub fn test_display() {
+        let numerical = play(&["5 5", "1 2"]);
+        let expected = "     |     |     
+  2  |     |     
+1    |2    |3    
+-----------------
+     |     |     
+     |  5  |     
+4    |5    |6    
+-----------------
+     |     |     
+     |     |     
+7    |8    |9    
+
+Odd left: 1 3 7 9
+Even left: 4 6 8
+";
+        assert_eq!(expected, numerical.to_string());
+        assert_eq!(
+            "Enter a cell and a number from 1 3 7 9 (e.g. 5 1)",
+            numerical.move_prompt(&Turn::Player1)
+        );
+    }
+
+    #[test]
+    pub fn test_random_players() {
+        let mut game = Game::new_with_board(Numerical::default());
+        game.set_verbose(false);
+        let mut player1 = RandomPlayer::new("one", Turn::Player1);
+        player1.set_seed(1);
+        let mut player2 = RandomPlayer::new("two", Turn::Player2);
+        player2.set_seed(2);
+        game.register_player(player1).unwrap();
+        game.register_player(player2).unwrap();
+        game.play().unwrap();
+        let board = game.board().board();
+        for number in 1..=9 {
+            assert_eq!(
+                board.contains(number),
+                game.history().iter().any(|m| m.location.number == number)
+            );
+        }
+    }
+}
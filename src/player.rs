@@ -1,27 +1,145 @@
 use crate::board::*;
+use crate::rules::RuleSet;
+use crate::search::SearchEngine;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
-use std::io::{self, Write};
+use rand::{thread_rng, Rng, SeedableRng};
+use std::fmt;
+use std::io::{self, BufRead, Read, Write};
 
-pub struct ScriptedPlayer {
+// Plays a fixed list of moves on any board whose moves and tokens they are.
+pub struct ScriptedPlayer<M = BoardLocation, T = BoardToken> {
     pub id: String,
-    pub token: BoardToken,
-    play_list: Vec<BoardLocation>,
+    pub token: T,
+    play_list: Vec<M>,
     play_index: usize,
 }
 
-pub struct InteractivePlayer {
+// A player who types their moves. Reads from standard input and prompts on
+// standard output unless given other streams with `with_io`.
+pub struct InteractivePlayer<R = ConsoleInput, W = io::Stdout, T = BoardToken> {
+    pub id: String,
+    pub token: T,
+    input: R,
+    output: W,
+}
+
+// Standard input, read a line at a time so that two interactive players can
+// share it without one buffering the other's moves.
+#[derive(Default)]
+pub struct ConsoleInput {
+    line: String,
+    position: usize,
+}
+
+pub struct MinimaxPlayer {
     pub id: String,
     pub token: BoardToken,
+    rules: RuleSet,
+}
+
+// Picks one of the legal moves at random. On the classic board each location
+// is chosen in proportion to its weight, indexed as `BoardLocation::index`.
+pub struct RandomPlayer<T = BoardToken> {
+    pub id: String,
+    pub token: T,
+    pub weights: [u32; 9],
+    rng: StdRng,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Perfect,
 }
 
+pub struct AiPlayer {
+    pub id: String,
+    pub token: BoardToken,
+    pub difficulty: Difficulty,
+    pub blunder_rate: f64,
+    rules: RuleSet,
+    engine: SearchEngine,
+    rng: StdRng,
+}
+
+#[derive(Debug, PartialEq)]
 pub enum PlayerError {
     NoMoreMoves,
     InvalidLocation,
 }
 
-impl ScriptedPlayer {
-    pub fn new(id: &str, token: BoardToken, play_list: &[BoardLocation]) -> Self {
+impl fmt::Display for PlayerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayerError::NoMoreMoves => write!(f, "the player has no more moves"),
+            PlayerError::InvalidLocation => write!(f, "that is not a location on the board"),
+        }
+    }
+}
+
+impl std::error::Error for PlayerError {}
+
+pub enum PlayerAction<B: Board = GameBoard> {
+    Play(B::Token, B::Move),
+    Undo,
+    Redo,
+}
+
+// A player of games on boards of type `B`, the classic board unless said
+// otherwise.
+pub trait Player<B: Board = GameBoard> {
+    fn id(&self) -> &str;
+    fn token(&self) -> B::Token;
+    fn play(&mut self, board: &B) -> Result<(B::Token, B::Move), PlayerError>;
+
+    fn next_action(&mut self, board: &B) -> Result<PlayerAction<B>, PlayerError> {
+        self.play(board)
+            .map(|(token, location)| PlayerAction::Play(token, location))
+    }
+
+    // Called by the game with the rules it is played under. Players who
+    // don't think about their moves can ignore it.
+    fn set_rules(&mut self, _rules: RuleSet) {}
+
+    // Whether a person is choosing the moves. A person who mistypes is asked
+    // again for as long as it takes; anyone else answering with something
+    // that isn't a move is dealt with like an illegal move.
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+impl<B: Board, P: Player<B> + ?Sized> Player<B> for Box<P> {
+    fn id(&self) -> &str {
+        (**self).id()
+    }
+
+    fn token(&self) -> B::Token {
+        (**self).token()
+    }
+
+    fn play(&mut self, board: &B) -> Result<(B::Token, B::Move), PlayerError> {
+        (**self).play(board)
+    }
+
+    fn next_action(&mut self, board: &B) -> Result<PlayerAction<B>, PlayerError> {
+        (**self).next_action(board)
+    }
+
+    fn set_rules(&mut self, rules: RuleSet) {
+        (**self).set_rules(rules)
+    }
+
+    fn is_interactive(&self) -> bool {
+        (**self).is_interactive()
+    }
+}
+
+impl<M: Clone, T> ScriptedPlayer<M, T> {
+    pub fn new(id: &str, token: T, play_list: &[M]) -> Self {
         Self {
             id: String::from(id),
             token,
@@ -29,28 +147,35 @@ impl ScriptedPlayer {
             play_index: 0,
         }
     }
+}
 
+impl ScriptedPlayer {
     pub fn new_random(id: &str, token: BoardToken) -> Self {
-        let mut vec: Vec<u32> = (1..=9).collect();
-        vec.shuffle(&mut thread_rng());
-        let play_list: Vec<BoardLocation> = vec
-            .iter()
-            .map(|i| match i {
-                1 => BoardLocation::TopLeft,
-                2 => BoardLocation::TopCentre,
-                3 => BoardLocation::TopRight,
-                4 => BoardLocation::MiddleLeft,
-                5 => BoardLocation::MiddleCentre,
-                6 => BoardLocation::MiddleRight,
-                7 => BoardLocation::BottomLeft,
-                8 => BoardLocation::BottomCentre,
-                9 => BoardLocation::BottomRight,
-                _ => unreachable!("vec above is defined with content 1 .. 9"),
-            })
-            .collect();
+        Self::new_random_with_rng(id, token, &mut thread_rng())
+    }
+
+    // The same seed always gives the same order of play.
+    pub fn new_random_with_seed(id: &str, token: BoardToken, seed: u64) -> Self {
+        Self::new_random_with_rng(id, token, &mut StdRng::seed_from_u64(seed))
+    }
+
+    pub fn new_random_with_rng<R: Rng + ?Sized>(id: &str, token: BoardToken, rng: &mut R) -> Self {
+        let mut play_list = BoardLocation::ALL.to_vec();
+        play_list.shuffle(rng);
         Self::new(id, token, &play_list)
     }
-    pub fn play(&mut self) -> Result<(BoardToken, BoardLocation), PlayerError> {
+}
+
+impl<B: Board> Player<B> for ScriptedPlayer<B::Move, B::Token> {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn token(&self) -> B::Token {
+        self.token.clone()
+    }
+
+    fn play(&mut self, _board: &B) -> Result<(B::Token, B::Move), PlayerError> {
         if self.play_index == self.play_list.len() {
             Err(PlayerError::NoMoreMoves)
         } else {
@@ -61,37 +186,668 @@ impl ScriptedPlayer {
     }
 }
 
-impl InteractivePlayer {
-    pub fn new(id: &str, token: BoardToken) -> Self {
+impl Read for ConsoleInput {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.consume(count);
+        Ok(count)
+    }
+}
+
+impl BufRead for ConsoleInput {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.position == self.line.len() {
+            self.line.clear();
+            self.position = 0;
+            io::stdin().read_line(&mut self.line)?;
+        }
+        Ok(&self.line.as_bytes()[self.position..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position = (self.position + amount).min(self.line.len());
+    }
+}
+
+impl<T> InteractivePlayer<ConsoleInput, io::Stdout, T> {
+    pub fn new(id: &str, token: T) -> Self {
+        Self::with_io(id, token, ConsoleInput::default(), io::stdout())
+    }
+}
+
+impl<R: BufRead, W: Write, T> InteractivePlayer<R, W, T> {
+    pub fn with_io(id: &str, token: T, input: R, output: W) -> Self {
         Self {
             id: String::from(id),
             token,
+            input,
+            output,
         }
     }
+}
+
+impl<B: Board, R: BufRead, W: Write> Player<B> for InteractivePlayer<R, W, B::Token> {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn token(&self) -> B::Token {
+        self.token.clone()
+    }
 
-    pub fn play(&self) -> Result<(BoardToken, BoardLocation), PlayerError> {
-        print!("Enter a location (1-9): ");
-        io::stdout().flush().expect("Error writing to screen");
+    fn play(&mut self, board: &B) -> Result<(B::Token, B::Move), PlayerError> {
+        match self.next_action(board)? {
+            PlayerAction::Play(token, mv) => Ok((token, mv)),
+            PlayerAction::Undo | PlayerAction::Redo => Err(PlayerError::InvalidLocation),
+        }
+    }
+
+    fn next_action(&mut self, board: &B) -> Result<PlayerAction<B>, PlayerError> {
+        write!(
+            self.output,
+            "{}, u to undo or r to redo: ",
+            board.move_prompt(&self.token)
+        )
+        .and_then(|_| self.output.flush())
+        .expect("Error writing to screen");
+        // Once the input runs dry the player can't make any more moves.
         let mut input = String::new();
-        if io::stdin().read_line(&mut input).is_err() {
-            return Err(PlayerError::InvalidLocation);
+        match self.input.read_line(&mut input) {
+            Ok(0) | Err(_) => return Err(PlayerError::NoMoreMoves),
+            Ok(_) => {}
         }
         let input = input.trim();
-        if let Ok(board_location) = input.parse::<u32>() {
-            match board_location {
-                1 => Ok((self.token.clone(), BoardLocation::TopLeft)),
-                2 => Ok((self.token.clone(), BoardLocation::TopCentre)),
-                3 => Ok((self.token.clone(), BoardLocation::TopRight)),
-                4 => Ok((self.token.clone(), BoardLocation::MiddleLeft)),
-                5 => Ok((self.token.clone(), BoardLocation::MiddleCentre)),
-                6 => Ok((self.token.clone(), BoardLocation::MiddleRight)),
-                7 => Ok((self.token.clone(), BoardLocation::BottomLeft)),
-                8 => Ok((self.token.clone(), BoardLocation::BottomCentre)),
-                9 => Ok((self.token.clone(), BoardLocation::BottomRight)),
-                _ => Err(PlayerError::InvalidLocation),
+        if input.eq_ignore_ascii_case("u") {
+            return Ok(PlayerAction::Undo);
+        }
+        if input.eq_ignore_ascii_case("r") {
+            return Ok(PlayerAction::Redo);
+        }
+        match input.parse() {
+            Ok(mv) => Ok(PlayerAction::Play(self.token.clone(), mv)),
+            Err(_) => Err(PlayerError::InvalidLocation),
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
+
+impl RandomPlayer {
+    pub const UNIFORM: [u32; 9] = [1; 9];
+    // Favours the centre, then the corners, over the edges.
+    pub const CENTRE_AND_CORNERS: [u32; 9] = [3, 1, 3, 1, 4, 1, 3, 1, 3];
+}
+
+impl<T> RandomPlayer<T> {
+    pub fn new(id: &str, token: T) -> Self {
+        Self::new_weighted(id, token, RandomPlayer::UNIFORM)
+    }
+
+    pub fn new_weighted(id: &str, token: T, weights: [u32; 9]) -> Self {
+        Self {
+            id: String::from(id),
+            token,
+            weights,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    // Makes the player's random choices repeatable.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    // Makes the player's random choices with `rng`, which may already have
+    // been used for something else.
+    pub fn with_rng(mut self, rng: StdRng) -> Self {
+        self.rng = rng;
+        self
+    }
+}
+
+impl<B: Board> Player<B> for RandomPlayer<B::Token> {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn token(&self) -> B::Token {
+        self.token.clone()
+    }
+
+    fn play(&mut self, board: &B) -> Result<(B::Token, B::Move), PlayerError> {
+        let moves = board.legal_moves(&self.token);
+        if moves.is_empty() {
+            return Err(PlayerError::NoMoreMoves);
+        }
+        // If every legal move has no weight, they are all equally likely.
+        let weight = |mv: &B::Move| {
+            board
+                .weight_index(mv)
+                .map_or(1, |index| self.weights[index])
+        };
+        let mv = moves
+            .choose_weighted(&mut self.rng, weight)
+            .ok()
+            .or_else(|| moves.choose(&mut self.rng))
+            .unwrap()
+            .clone();
+        Ok((self.token.clone(), mv))
+    }
+}
+
+impl MinimaxPlayer {
+    pub fn new(id: &str, token: BoardToken) -> Self {
+        Self {
+            id: String::from(id),
+            token,
+            rules: RuleSet::default(),
+        }
+    }
+
+    // Scores are from this player's point of view. Quicker wins and slower
+    // losses are preferred by folding the search depth into the score.
+    fn minimax(&self, board: &GameBoard, to_move: BoardToken, depth: i32) -> i32 {
+        if let Some((completed_by, _)) = board.winner() {
+            return if self.rules.winner(&completed_by) == self.token {
+                10 - depth
+            } else {
+                depth - 10
+            };
+        }
+        if board.is_full() {
+            return 0;
+        }
+        let scores = board.empty_locations().into_iter().map(|location| {
+            let mut next = board.clone();
+            next.place(location.clone(), to_move.clone())
+                .expect("location is known to be free");
+            self.minimax(&next, to_move.opponent(), depth + 1)
+        });
+        if to_move == self.token {
+            scores.max().unwrap()
+        } else {
+            scores.min().unwrap()
+        }
+    }
+}
+
+impl Player for MinimaxPlayer {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn token(&self) -> BoardToken {
+        self.token.clone()
+    }
+
+    fn play(&mut self, board: &GameBoard) -> Result<(BoardToken, BoardLocation), PlayerError> {
+        let mut best: Option<(i32, BoardLocation)> = None;
+        for location in board.empty_locations() {
+            let mut next = board.clone();
+            next.place(location.clone(), self.token.clone())
+                .expect("location is known to be free");
+            let score = self.minimax(&next, self.token.opponent(), 1);
+            if best
+                .as_ref()
+                .is_none_or(|(best_score, _)| score > *best_score)
+            {
+                best = Some((score, location.clone()));
             }
+        }
+        best.map(|(_, location)| (self.token.clone(), location))
+            .ok_or(PlayerError::NoMoreMoves)
+    }
+
+    fn set_rules(&mut self, rules: RuleSet) {
+        self.rules = rules;
+    }
+}
+
+// Who has won, if anyone, as soon as `token` is played at `location`.
+fn decided_by(
+    board: &GameBoard,
+    location: &BoardLocation,
+    token: &BoardToken,
+    rules: RuleSet,
+) -> Option<BoardToken> {
+    let mut next = board.clone();
+    next.place(location.clone(), token.clone())
+        .expect("location is known to be free");
+    next.winner()
+        .map(|(completed_by, _)| rules.winner(&completed_by))
+}
+
+fn winning_location(
+    board: &GameBoard,
+    token: &BoardToken,
+    rules: RuleSet,
+) -> Option<BoardLocation> {
+    board
+        .empty_locations()
+        .into_iter()
+        .find(|location| decided_by(board, location, token, rules).as_ref() == Some(token))
+}
+
+// Depth-limited negamax for `Difficulty::Hard`. Positions that are still
+// undecided at the horizon are scored as a draw.
+fn lookahead(board: &GameBoard, to_move: &BoardToken, rules: RuleSet, depth: u32) -> i32 {
+    if let Some((completed_by, _)) = board.winner() {
+        return if &rules.winner(&completed_by) == to_move {
+            10
         } else {
-            Err(PlayerError::InvalidLocation)
+            -10
+        };
+    }
+    if board.is_full() || depth == 0 {
+        return 0;
+    }
+    board
+        .empty_locations()
+        .into_iter()
+        .map(|location| {
+            let mut next = board.clone();
+            next.place(location, to_move.clone())
+                .expect("location is known to be free");
+            -lookahead(&next, &to_move.opponent(), rules, depth - 1)
+        })
+        .max()
+        .unwrap()
+}
+
+impl Difficulty {
+    pub fn default_blunder_rate(&self) -> f64 {
+        match self {
+            Difficulty::Easy => 0.5,
+            Difficulty::Medium => 0.2,
+            Difficulty::Hard => 0.1,
+            Difficulty::Perfect => 0.0,
+        }
+    }
+}
+
+impl AiPlayer {
+    const HARD_LOOKAHEAD: u32 = 3;
+
+    pub fn new(id: &str, token: BoardToken, difficulty: Difficulty) -> Self {
+        let blunder_rate = difficulty.default_blunder_rate();
+        Self::new_with_blunder_rate(id, token, difficulty, blunder_rate)
+    }
+
+    pub fn new_with_blunder_rate(
+        id: &str,
+        token: BoardToken,
+        difficulty: Difficulty,
+        blunder_rate: f64,
+    ) -> Self {
+        Self {
+            id: String::from(id),
+            token,
+            difficulty,
+            blunder_rate,
+            rules: RuleSet::default(),
+            engine: SearchEngine::new(),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    // Makes the player's random choices repeatable.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    // Makes the player's random choices with `rng`, which may already have
+    // been used for something else.
+    pub fn with_rng(mut self, rng: StdRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    // Swaps a random move that would lose on the spot for one that doesn't,
+    // if there is one. Only misère has such moves.
+    fn avoid_losing(
+        &mut self,
+        board: &GameBoard,
+        moves: &[BoardLocation],
+        random: BoardLocation,
+    ) -> BoardLocation {
+        let opponent = Some(self.token.opponent());
+        if decided_by(board, &random, &self.token, self.rules) != opponent {
+            return random;
+        }
+        let safe: Vec<&BoardLocation> = moves
+            .iter()
+            .filter(|location| decided_by(board, location, &self.token, self.rules) != opponent)
+            .collect();
+        safe.choose(&mut self.rng)
+            .map(|location| (*location).clone())
+            .unwrap_or(random)
+    }
+
+    fn choose(&mut self, board: &GameBoard, moves: &[BoardLocation]) -> BoardLocation {
+        let random = moves.choose(&mut self.rng).unwrap().clone();
+        match self.difficulty {
+            Difficulty::Easy => {
+                if self.rng.gen_bool(0.5) {
+                    match winning_location(board, &self.token, self.rules) {
+                        Some(location) => location,
+                        None => self.avoid_losing(board, moves, random),
+                    }
+                } else {
+                    random
+                }
+            }
+            Difficulty::Medium => match winning_location(board, &self.token, self.rules)
+                .or_else(|| winning_location(board, &self.token.opponent(), self.rules))
+            {
+                Some(location) => location,
+                None => self.avoid_losing(board, moves, random),
+            },
+            Difficulty::Hard => {
+                let scored: Vec<(i32, BoardLocation)> = moves
+                    .iter()
+                    .map(|location| {
+                        let mut next = board.clone();
+                        next.place(location.clone(), self.token.clone())
+                            .expect("location is known to be free");
+                        let score = -lookahead(
+                            &next,
+                            &self.token.opponent(),
+                            self.rules,
+                            Self::HARD_LOOKAHEAD - 1,
+                        );
+                        (score, location.clone())
+                    })
+                    .collect();
+                let best = scored.iter().map(|(score, _)| *score).max().unwrap();
+                let best_moves: Vec<BoardLocation> = scored
+                    .into_iter()
+                    .filter(|(score, _)| *score == best)
+                    .map(|(_, location)| location)
+                    .collect();
+                best_moves.choose(&mut self.rng).unwrap().clone()
+            }
+            Difficulty::Perfect => self
+                .engine
+                .search(board, self.token.clone())
+                .best_move
+                .unwrap_or(random),
+        }
+    }
+}
+
+impl Player for AiPlayer {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn token(&self) -> BoardToken {
+        self.token.clone()
+    }
+
+    fn play(&mut self, board: &GameBoard) -> Result<(BoardToken, BoardLocation), PlayerError> {
+        let moves = board.empty_locations();
+        if moves.is_empty() {
+            return Err(PlayerError::NoMoreMoves);
+        }
+        let chosen = self.choose(board, &moves);
+        if moves.len() > 1 && self.rng.gen_bool(self.blunder_rate.clamp(0.0, 1.0)) {
+            let blunders: Vec<&BoardLocation> = moves.iter().filter(|m| **m != chosen).collect();
+            return Ok((
+                self.token.clone(),
+                (*blunders.choose(&mut self.rng).unwrap()).clone(),
+            ));
+        }
+        Ok((self.token.clone(), chosen))
+    }
+
+    // The search engine's table only holds for one set of rules, so a new
+    // engine is started when they change.
+    fn set_rules(&mut self, rules: RuleSet) {
+        if rules != self.rules {
+            self.rules = rules;
+            self.engine = SearchEngine::new_with_rules(rules);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board_with(moves: &[(BoardLocation, BoardToken)]) -> GameBoard {
+        let mut board = GameBoard::default();
+        for (location, token) in moves {
+            board.place(location.clone(), token.clone()).unwrap();
+        }
+        board
+    }
+
+    #[test]
+    pub fn test_minimax_takes_winning_move() {
+        let board = board_with(&[
+            (BoardLocation::TopLeft, BoardToken::Cross),
+            (BoardLocation::MiddleLeft, BoardToken::Nought),
+            (BoardLocation::TopCentre, BoardToken::Cross),
+            (BoardLocation::MiddleCentre, BoardToken::Nought),
+        ]);
+        let mut player = MinimaxPlayer::new("minimax", BoardToken::Cross);
+        let (token, location) = player.play(&board).ok().unwrap();
+        assert_eq!(BoardToken::Cross, token);
+        assert_eq!(BoardLocation::TopRight, location);
+    }
+
+    #[test]
+    pub fn test_minimax_blocks_opponent() {
+        let board = board_with(&[
+            (BoardLocation::TopLeft, BoardToken::Cross),
+            (BoardLocation::MiddleCentre, BoardToken::Nought),
+            (BoardLocation::TopCentre, BoardToken::Cross),
+        ]);
+        let mut player = MinimaxPlayer::new("minimax", BoardToken::Nought);
+        let (_, location) = player.play(&board).ok().unwrap();
+        assert_eq!(BoardLocation::TopRight, location);
+    }
+
+    #[test]
+    pub fn test_minimax_on_full_board() {
+        let board = board_with(&[
+            (BoardLocation::MiddleCentre, BoardToken::Cross),
+            (BoardLocation::TopLeft, BoardToken::Nought),
+            (BoardLocation::TopCentre, BoardToken::Cross),
+            (BoardLocation::BottomCentre, BoardToken::Nought),
+            (BoardLocation::MiddleRight, BoardToken::Cross),
+            (BoardLocation::MiddleLeft, BoardToken::Nought),
+            (BoardLocation::BottomLeft, BoardToken::Cross),
+            (BoardLocation::TopRight, BoardToken::Nought),
+            (BoardLocation::BottomRight, BoardToken::Cross),
+        ]);
+        let mut player = MinimaxPlayer::new("minimax", BoardToken::Nought);
+        assert!(matches!(player.play(&board), Err(PlayerError::NoMoreMoves)));
+    }
+
+    #[test]
+    pub fn test_medium_takes_win_before_block() {
+        let board = board_with(&[
+            (BoardLocation::TopLeft, BoardToken::Cross),
+            (BoardLocation::MiddleLeft, BoardToken::Nought),
+            (BoardLocation::TopCentre, BoardToken::Cross),
+            (BoardLocation::MiddleCentre, BoardToken::Nought),
+        ]);
+        let mut player =
+            AiPlayer::new_with_blunder_rate("medium", BoardToken::Nought, Difficulty::Medium, 0.0);
+        let (_, location) = player.play(&board).ok().unwrap();
+        assert_eq!(BoardLocation::MiddleRight, location);
+    }
+
+    #[test]
+    pub fn test_misere_players_avoid_completing_a_line() {
+        // The top right would give crosses three in a row, and so the game.
+        let board = board_with(&[
+            (BoardLocation::TopLeft, BoardToken::Cross),
+            (BoardLocation::MiddleLeft, BoardToken::Nought),
+            (BoardLocation::TopCentre, BoardToken::Cross),
+            (BoardLocation::MiddleCentre, BoardToken::Nought),
+        ]);
+        for difficulty in [Difficulty::Medium, Difficulty::Hard, Difficulty::Perfect] {
+            for seed in 0..10 {
+                let mut player = AiPlayer::new_with_blunder_rate(
+                    "ai",
+                    BoardToken::Cross,
+                    difficulty.clone(),
+                    0.0,
+                );
+                player.set_seed(seed);
+                player.set_rules(RuleSet::Misere);
+                let (_, location) = player.play(&board).ok().unwrap();
+                assert_ne!(BoardLocation::TopRight, location);
+            }
+        }
+        let mut minimax = MinimaxPlayer::new("minimax", BoardToken::Cross);
+        minimax.set_rules(RuleSet::Misere);
+        let (_, location) = minimax.play(&board).ok().unwrap();
+        assert_ne!(BoardLocation::TopRight, location);
+    }
+
+    #[test]
+    pub fn test_medium_blocks_opponent() {
+        let board = board_with(&[
+            (BoardLocation::TopLeft, BoardToken::Cross),
+            (BoardLocation::MiddleCentre, BoardToken::Nought),
+            (BoardLocation::TopCentre, BoardToken::Cross),
+        ]);
+        let mut player =
+            AiPlayer::new_with_blunder_rate("medium", BoardToken::Nought, Difficulty::Medium, 0.0);
+        let (_, location) = player.play(&board).ok().unwrap();
+        assert_eq!(BoardLocation::TopRight, location);
+    }
+
+    #[test]
+    pub fn test_hard_sees_fork() {
+        // Cross to move can fork with the bottom right corner.
+        let board = board_with(&[
+            (BoardLocation::TopLeft, BoardToken::Cross),
+            (BoardLocation::TopCentre, BoardToken::Nought),
+            (BoardLocation::MiddleCentre, BoardToken::Cross),
+            (BoardLocation::BottomRight, BoardToken::Nought),
+        ]);
+        let mut player =
+            AiPlayer::new_with_blunder_rate("hard", BoardToken::Cross, Difficulty::Hard, 0.0);
+        let (_, location) = player.play(&board).ok().unwrap();
+        assert!(
+            location == BoardLocation::BottomLeft || location == BoardLocation::MiddleLeft,
+            "{:?}",
+            location
+        );
+    }
+
+    #[test]
+    pub fn test_blunder_avoids_best_move() {
+        let board = board_with(&[
+            (BoardLocation::TopLeft, BoardToken::Cross),
+            (BoardLocation::MiddleLeft, BoardToken::Nought),
+            (BoardLocation::TopCentre, BoardToken::Cross),
+            (BoardLocation::MiddleCentre, BoardToken::Nought),
+        ]);
+        let mut player =
+            AiPlayer::new_with_blunder_rate("perfect", BoardToken::Cross, Difficulty::Perfect, 1.0);
+        for _ in 0..10 {
+            let (_, location) = player.play(&board).ok().unwrap();
+            assert_ne!(BoardLocation::TopRight, location);
+            assert!(board.get(location).is_none());
+        }
+    }
+
+    #[test]
+    pub fn test_seeded_random_player() {
+        let play_all = |mut player: ScriptedPlayer| -> Vec<BoardLocation> {
+            (0..9)
+                .map(|_| player.play(&GameBoard::default()).unwrap().1)
+                .collect()
+        };
+        let first = play_all(ScriptedPlayer::new_random_with_seed(
+            "a",
+            BoardToken::Cross,
+            7,
+        ));
+        let second = play_all(ScriptedPlayer::new_random_with_seed(
+            "b",
+            BoardToken::Cross,
+            7,
+        ));
+        assert_eq!(first, second);
+        let mut rng = StdRng::seed_from_u64(7);
+        let third = play_all(ScriptedPlayer::new_random_with_rng(
+            "c",
+            BoardToken::Cross,
+            &mut rng,
+        ));
+        assert_eq!(first, third);
+    }
+
+    #[test]
+    pub fn test_seeded_ai_player() {
+        let choices = || {
+            let mut player = AiPlayer::new("easy", BoardToken::Cross, Difficulty::Easy);
+            player.set_seed(42);
+            (0..20)
+                .map(|_| player.play(&GameBoard::default()).unwrap().1)
+                .collect::<Vec<BoardLocation>>()
+        };
+        assert_eq!(choices(), choices());
+    }
+
+    #[test]
+    pub fn test_players_with_rng() {
+        let board = GameBoard::default();
+        let mut random =
+            RandomPlayer::new("random", BoardToken::Cross).with_rng(StdRng::seed_from_u64(3));
+        let mut seeded = RandomPlayer::new("seeded", BoardToken::Cross);
+        seeded.set_seed(3);
+        let mut ai = AiPlayer::new("easy", BoardToken::Cross, Difficulty::Easy)
+            .with_rng(StdRng::seed_from_u64(3));
+        let mut seeded_ai = AiPlayer::new("seeded", BoardToken::Cross, Difficulty::Easy);
+        seeded_ai.set_seed(3);
+        for _ in 0..20 {
+            assert_eq!(seeded.play(&board), random.play(&board));
+            assert_eq!(seeded_ai.play(&board), ai.play(&board));
+        }
+    }
+
+    #[test]
+    pub fn test_random_player_picks_free_locations() {
+        let board = board_with(&[
+            (BoardLocation::TopLeft, BoardToken::Cross),
+            (BoardLocation::MiddleCentre, BoardToken::Nought),
+            (BoardLocation::BottomRight, BoardToken::Cross),
+        ]);
+        let mut player = RandomPlayer::new("random", BoardToken::Nought);
+        player.set_seed(1);
+        for _ in 0..50 {
+            let (token, location) = player.play(&board).unwrap();
+            assert_eq!(BoardToken::Nought, token);
+            assert!(board.get(location).is_none());
+        }
+        let full: GameBoard = "XOX/XOO/OXX".parse().unwrap();
+        assert_eq!(Err(PlayerError::NoMoreMoves), player.play(&full));
+    }
+
+    #[test]
+    pub fn test_weighted_random_player() {
+        // Only the corners carry any weight.
+        let mut player =
+            RandomPlayer::new_weighted("corners", BoardToken::Cross, [1, 0, 1, 0, 0, 0, 1, 0, 1]);
+        for _ in 0..50 {
+            let (_, location) = player.play(&GameBoard::default()).unwrap();
+            assert_eq!(0, location.index() % 2);
+            assert_ne!(BoardLocation::MiddleCentre, location);
         }
+        // With the corners taken, the zero weights are all that is left.
+        let board: GameBoard = "X.O/.../O.X".parse().unwrap();
+        let (_, location) = player.play(&board).unwrap();
+        assert!(board.get(location).is_none());
     }
 }
@@ -0,0 +1,618 @@
+use crate::board::*;
+use crate::rules::RuleSet;
+use crate::{GameResult, Turn, Win};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const HEADER: &str = "xors-record 1";
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RecordedPlayer<T = BoardToken> {
+    pub id: String,
+    pub token: T,
+}
+
+// A saved game. Player1 always makes the first move and the players then
+// alternate, so only the locations need to be stored. A `None` move is a turn
+// that was skipped. `seed` is the seed the random players were given, if any.
+// `rules` decide who won; standard rules are left out of the saved text.
+// `forfeit` is the player who forfeited, which ends the game whatever the
+// board shows.
+#[derive(Debug, PartialEq, Clone)]
+pub struct GameRecord {
+    pub player1: RecordedPlayer,
+    pub player2: RecordedPlayer,
+    pub moves: Vec<Option<BoardLocation>>,
+    pub started: u64,
+    pub updated: u64,
+    pub seed: Option<u64>,
+    pub rules: RuleSet,
+    pub forfeit: Option<Turn>,
+}
+
+#[derive(Debug)]
+pub enum RecordError {
+    Io(io::Error),
+    BadHeader(String),
+    MissingField(&'static str),
+    DuplicateField(String),
+    BadField(&'static str, String),
+    IllegalMove {
+        number: usize,
+        location: Option<BoardLocation>,
+    },
+    ResultMismatch {
+        recorded: String,
+        actual: String,
+    },
+    PlayerMismatch,
+    DuplicateToken(BoardToken),
+}
+
+impl From<io::Error> for RecordError {
+    fn from(err: io::Error) -> Self {
+        RecordError::Io(err)
+    }
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordError::Io(err) => write!(f, "{}", err),
+            RecordError::BadHeader(header) => {
+                write!(f, "{:?} is not a game record header", header)
+            }
+            RecordError::MissingField(field) => write!(f, "the {} field is missing", field),
+            RecordError::DuplicateField(field) => {
+                write!(f, "the {} field appears more than once", field)
+            }
+            RecordError::BadField(field, value) => {
+                write!(f, "{:?} is not a valid {} field", value, field)
+            }
+            RecordError::IllegalMove {
+                number,
+                location: Some(location),
+            } => write!(f, "move {} at {} is illegal", number, location),
+            RecordError::IllegalMove {
+                number,
+                location: None,
+            } => write!(f, "move {} skips a turn after the game ended", number),
+            RecordError::ResultMismatch { recorded, actual } => write!(
+                f,
+                "the result is recorded as {} but the moves give {}",
+                recorded, actual
+            ),
+            RecordError::PlayerMismatch => {
+                write!(f, "the players don't hold the recorded tokens")
+            }
+            RecordError::DuplicateToken(token) => {
+                write!(f, "both players are recorded as playing {}", token)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RecordError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RecordError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+fn result_name(result: &Option<GameResult>) -> &'static str {
+    match result {
+        Some(GameResult::Player1(_)) => "player1",
+        Some(GameResult::Player2(_)) => "player2",
+        Some(GameResult::Tie) => "tie",
+        None => "unfinished",
+    }
+}
+
+fn turn_name(turn: Turn) -> &'static str {
+    match turn {
+        Turn::Player1 => "player1",
+        Turn::Player2 => "player2",
+    }
+}
+
+fn parse_turn(field: &'static str, value: &str) -> Result<Turn, RecordError> {
+    match value {
+        "player1" => Ok(Turn::Player1),
+        "player2" => Ok(Turn::Player2),
+        _ => Err(RecordError::BadField(field, value.into())),
+    }
+}
+
+fn parse_player(field: &'static str, value: &str) -> Result<RecordedPlayer, RecordError> {
+    let (token, id) = value
+        .split_once(' ')
+        .ok_or_else(|| RecordError::BadField(field, value.into()))?;
+    let token = match token {
+        "X" => BoardToken::Cross,
+        "O" => BoardToken::Nought,
+        _ => return Err(RecordError::BadField(field, value.into())),
+    };
+    Ok(RecordedPlayer {
+        id: id.into(),
+        token,
+    })
+}
+
+fn parse_timestamp(field: &'static str, value: &str) -> Result<u64, RecordError> {
+    value
+        .parse()
+        .map_err(|_| RecordError::BadField(field, value.into()))
+}
+
+fn parse_moves(value: &str) -> Result<Vec<Option<BoardLocation>>, RecordError> {
+    value
+        .split_whitespace()
+        .map(|number| {
+            if number == "-" {
+                return Ok(None);
+            }
+            number
+                .parse()
+                .ok()
+                .and_then(BoardLocation::from_number)
+                .map(Some)
+                .ok_or_else(|| RecordError::BadField("moves", number.into()))
+        })
+        .collect()
+}
+
+impl GameRecord {
+    pub fn token_for_move(&self, index: usize) -> BoardToken {
+        if index.is_multiple_of(2) {
+            self.player1.token.clone()
+        } else {
+            self.player2.token.clone()
+        }
+    }
+
+    // Every position in the game, starting with the empty board. Fails on the
+    // first move that is not legal.
+    pub fn positions(&self) -> Result<Vec<GameBoard>, RecordError> {
+        let mut board = GameBoard::default();
+        let mut positions = vec![board.clone()];
+        for (index, location) in self.moves.iter().enumerate() {
+            let illegal = RecordError::IllegalMove {
+                number: index + 1,
+                location: location.clone(),
+            };
+            if self.rules.result(&board, &self.player1.token).is_some() {
+                return Err(illegal);
+            }
+            if let Some(location) = location {
+                if board
+                    .place(location.clone(), self.token_for_move(index))
+                    .is_err()
+                {
+                    return Err(illegal);
+                }
+            }
+            positions.push(board.clone());
+        }
+        Ok(positions)
+    }
+
+    pub fn board(&self) -> Result<GameBoard, RecordError> {
+        Ok(self.positions()?.pop().unwrap())
+    }
+
+    pub fn result(&self) -> Result<Option<GameResult>, RecordError> {
+        let board = self.board()?;
+        let Some(loser) = self.forfeit else {
+            return Ok(self.rules.result(&board, &self.player1.token));
+        };
+        let win = |winner: &RecordedPlayer| Win {
+            token: winner.token.clone(),
+            lines: Vec::new(),
+            forfeit: true,
+        };
+        Ok(Some(match loser {
+            Turn::Player1 => GameResult::Player2(win(&self.player2)),
+            Turn::Player2 => GameResult::Player1(win(&self.player1)),
+        }))
+    }
+
+    // Each player's id has to fit on its line after the token, or the saved
+    // record couldn't be loaded again.
+    fn check_ids(&self) -> Result<(), RecordError> {
+        for (field, player) in [("player1", &self.player1), ("player2", &self.player2)] {
+            if player.id.trim().is_empty() || player.id.contains(['\n', '\r']) {
+                return Err(RecordError::BadField(field, player.id.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    // The checks loading makes beyond the format: the players hold different
+    // tokens, the moves can be played out and nobody forfeits a game that was
+    // already decided.
+    fn validate(&self) -> Result<(), RecordError> {
+        if self.player1.token == self.player2.token {
+            return Err(RecordError::DuplicateToken(self.player1.token.clone()));
+        }
+        let board = self.board()?;
+        if let Some(loser) = self.forfeit {
+            if self.rules.result(&board, &self.player1.token).is_some() {
+                return Err(RecordError::BadField("forfeit", turn_name(loser).into()));
+            }
+        }
+        Ok(())
+    }
+
+    // Refuses to write a record that `load` would reject.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), RecordError> {
+        self.check_ids()?;
+        self.validate()?;
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<GameRecord, RecordError> {
+        fs::read_to_string(path)?.parse()
+    }
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let moves: Vec<String> = self
+            .moves
+            .iter()
+            .map(|location| match location {
+                Some(location) => location.number().to_string(),
+                None => "-".into(),
+            })
+            .collect();
+        let result = self.result().ok().flatten();
+        writeln!(f, "{}", HEADER)?;
+        writeln!(f, "player1: {} {}", self.player1.token, self.player1.id)?;
+        writeln!(f, "player2: {} {}", self.player2.token, self.player2.id)?;
+        writeln!(f, "started: {}", self.started)?;
+        writeln!(f, "updated: {}", self.updated)?;
+        if let Some(seed) = self.seed {
+            writeln!(f, "seed: {}", seed)?;
+        }
+        if self.rules != RuleSet::Standard {
+            writeln!(f, "rules: {}", self.rules)?;
+        }
+        writeln!(f, "moves: {}", moves.join(" "))?;
+        if let Some(loser) = self.forfeit {
+            writeln!(f, "forfeit: {}", turn_name(loser))?;
+        }
+        writeln!(f, "result: {}", result_name(&result))
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = RecordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().map(str::trim).filter(|line| !line.is_empty());
+        match lines.next() {
+            Some(HEADER) => {}
+            other => return Err(RecordError::BadHeader(other.unwrap_or_default().into())),
+        }
+
+        let mut player1 = None;
+        let mut player2 = None;
+        let mut started = None;
+        let mut updated = None;
+        let mut seed = None;
+        let mut rules = RuleSet::default();
+        let mut moves = None;
+        let mut forfeit = None;
+        let mut result = None;
+        let mut seen = HashSet::new();
+        for line in lines {
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| RecordError::BadField("line", line.into()))?;
+            let key = key.trim();
+            if !seen.insert(key) {
+                return Err(RecordError::DuplicateField(key.into()));
+            }
+            let value = value.trim();
+            match key {
+                "player1" => player1 = Some(parse_player("player1", value)?),
+                "player2" => player2 = Some(parse_player("player2", value)?),
+                "started" => started = Some(parse_timestamp("started", value)?),
+                "updated" => updated = Some(parse_timestamp("updated", value)?),
+                "seed" => {
+                    seed = Some(
+                        value
+                            .parse()
+                            .map_err(|_| RecordError::BadField("seed", value.into()))?,
+                    )
+                }
+                "rules" => {
+                    rules = RuleSet::from_name(value)
+                        .ok_or_else(|| RecordError::BadField("rules", value.into()))?
+                }
+                "moves" => moves = Some(parse_moves(value)?),
+                "forfeit" => forfeit = Some(parse_turn("forfeit", value)?),
+                "result" => result = Some(value.to_string()),
+                _ => return Err(RecordError::BadField("line", line.into())),
+            }
+        }
+
+        let record = GameRecord {
+            player1: player1.ok_or(RecordError::MissingField("player1"))?,
+            player2: player2.ok_or(RecordError::MissingField("player2"))?,
+            moves: moves.ok_or(RecordError::MissingField("moves"))?,
+            started: started.ok_or(RecordError::MissingField("started"))?,
+            updated: updated.ok_or(RecordError::MissingField("updated"))?,
+            seed,
+            rules,
+            forfeit,
+        };
+        record.validate()?;
+        let recorded = result.ok_or(RecordError::MissingField("result"))?;
+        let actual = result_name(&record.result()?);
+        if recorded != actual {
+            return Err(RecordError::ResultMismatch {
+                recorded,
+                actual: actual.into(),
+            });
+        }
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A move of 0 stands for a skipped turn.
+    fn record(moves: &[u32]) -> GameRecord {
+        GameRecord {
+            player1: RecordedPlayer {
+                id: "Yasmin".into(),
+                token: BoardToken::Cross,
+            },
+            player2: RecordedPlayer {
+                id: "Mummy Bear".into(),
+                token: BoardToken::Nought,
+            },
+            moves: moves
+                .iter()
+                .map(|number| BoardLocation::from_number(*number))
+                .collect(),
+            started: 1700000000,
+            updated: 1700000060,
+            seed: None,
+            rules: RuleSet::Standard,
+            forfeit: None,
+        }
+    }
+
+    #[test]
+    pub fn test_round_trip() {
+        let record = record(&[5, 1, 2, 4, 8]);
+        let text = record.to_string();
+        assert!(text.contains("moves: 5 1 2 4 8\n"));
+        assert!(text.contains("player2: O Mummy Bear\n"));
+        assert!(text.contains("result: player1\n"));
+        assert_eq!(record, text.parse().unwrap());
+    }
+
+    #[test]
+    pub fn test_unfinished_game() {
+        let record = record(&[5, 1]);
+        assert!(record.to_string().contains("result: unfinished\n"));
+        assert_eq!(None, record.result().unwrap());
+        assert_eq!(3, record.positions().unwrap().len());
+    }
+
+    #[test]
+    pub fn test_skipped_turn() {
+        let record = record(&[5, 0, 1]);
+        assert_eq!(None, record.moves[1]);
+        let text = record.to_string();
+        assert!(text.contains("moves: 5 - 1\n"));
+        assert_eq!(record, text.parse().unwrap());
+        assert_eq!(
+            Some(BoardToken::Cross),
+            record.board().unwrap().get(BoardLocation::TopLeft)
+        );
+    }
+
+    #[test]
+    pub fn test_seed() {
+        let mut record = record(&[5, 1]);
+        assert!(!record.to_string().contains("seed:"));
+        record.seed = Some(42);
+        let text = record.to_string();
+        assert!(text.contains("seed: 42\n"));
+        assert_eq!(record, text.parse().unwrap());
+        assert!(matches!(
+            text.replace("seed: 42", "seed: lots").parse::<GameRecord>(),
+            Err(RecordError::BadField("seed", _))
+        ));
+    }
+
+    #[test]
+    pub fn test_rules() {
+        let mut record = record(&[1, 4, 2, 5, 3]);
+        let text = record.to_string();
+        assert!(!text.contains("rules:"));
+        assert!(text.contains("result: player1\n"));
+        record.rules = RuleSet::Misere;
+        let text = record.to_string();
+        assert!(text.contains("rules: misere\n"));
+        assert!(text.contains("result: player2\n"));
+        assert_eq!(record, text.parse().unwrap());
+        assert!(matches!(
+            text.replace("rules: misere", "rules: reverse")
+                .parse::<GameRecord>(),
+            Err(RecordError::BadField("rules", _))
+        ));
+    }
+
+    #[test]
+    pub fn test_forfeit() {
+        let decided = record(&[5, 1, 2, 4, 8]);
+        let mut record = record(&[5, 1]);
+        record.forfeit = Some(Turn::Player2);
+        let text = record.to_string();
+        assert!(text.contains("forfeit: player2\n"));
+        assert!(text.contains("result: player1\n"));
+        assert_eq!(record, text.parse().unwrap());
+        assert_eq!(
+            Some(GameResult::Player1(Win {
+                token: BoardToken::Cross,
+                lines: vec![],
+                forfeit: true,
+            })),
+            record.result().unwrap()
+        );
+        assert!(matches!(
+            text.replace("forfeit: player2", "forfeit: nobody")
+                .parse::<GameRecord>(),
+            Err(RecordError::BadField("forfeit", _))
+        ));
+        // These moves have already won the game for player1.
+        let decided = decided
+            .to_string()
+            .replace("result:", "forfeit: player2\nresult:");
+        assert!(matches!(
+            decided.parse::<GameRecord>(),
+            Err(RecordError::BadField("forfeit", _))
+        ));
+    }
+
+    #[test]
+    pub fn test_duplicate_tokens() {
+        let text = record(&[5]).to_string().replace("player2: O", "player2: X");
+        assert!(matches!(
+            text.parse::<GameRecord>(),
+            Err(RecordError::DuplicateToken(BoardToken::Cross))
+        ));
+    }
+
+    #[test]
+    pub fn test_ids_that_would_not_load() {
+        let path = std::env::temp_dir().join(format!("xors-bad-id-{}.txt", std::process::id()));
+        for id in ["", " ", "Mummy\nBear"] {
+            let mut record = record(&[5]);
+            record.player2.id = id.into();
+            assert!(matches!(
+                record.save(&path),
+                Err(RecordError::BadField("player2", _))
+            ));
+            assert!(!path.exists());
+        }
+    }
+
+    #[test]
+    pub fn test_illegal_records_are_not_saved() {
+        let path = std::env::temp_dir().join(format!("xors-illegal-{}.txt", std::process::id()));
+        assert!(matches!(
+            record(&[5, 5]).save(&path),
+            Err(RecordError::IllegalMove { number: 2, .. })
+        ));
+        let mut record = record(&[5]);
+        record.player2.token = BoardToken::Cross;
+        assert!(matches!(
+            record.save(&path),
+            Err(RecordError::DuplicateToken(BoardToken::Cross))
+        ));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    pub fn test_duplicate_field() {
+        let text = record(&[5])
+            .to_string()
+            .replace("moves: 5\n", "moves: 5\nmoves: 1\n");
+        assert!(matches!(
+            text.parse::<GameRecord>(),
+            Err(RecordError::DuplicateField(field)) if field == "moves"
+        ));
+    }
+
+    #[test]
+    pub fn test_bad_header() {
+        assert!(matches!(
+            "not a record".parse::<GameRecord>(),
+            Err(RecordError::BadHeader(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_missing_field() {
+        let text = record(&[5])
+            .to_string()
+            .replace("started: 1700000000\n", "");
+        assert!(matches!(
+            text.parse::<GameRecord>(),
+            Err(RecordError::MissingField("started"))
+        ));
+    }
+
+    #[test]
+    pub fn test_bad_move() {
+        let text = record(&[5]).to_string().replace("moves: 5", "moves: 5 10");
+        assert!(matches!(
+            text.parse::<GameRecord>(),
+            Err(RecordError::BadField("moves", _))
+        ));
+    }
+
+    #[test]
+    pub fn test_illegal_move() {
+        let text = record(&[5]).to_string().replace("moves: 5", "moves: 5 5");
+        assert!(matches!(
+            text.parse::<GameRecord>(),
+            Err(RecordError::IllegalMove {
+                number: 2,
+                location: Some(BoardLocation::MiddleCentre)
+            })
+        ));
+        let text = record(&[5, 1, 2, 4, 8])
+            .to_string()
+            .replace("moves: 5 1 2 4 8", "moves: 5 1 2 4 8 9");
+        assert!(matches!(
+            text.parse::<GameRecord>(),
+            Err(RecordError::IllegalMove { number: 6, .. })
+        ));
+    }
+
+    #[test]
+    pub fn test_result_mismatch() {
+        let text = record(&[5, 1])
+            .to_string()
+            .replace("result: unfinished", "result: tie");
+        assert!(matches!(
+            text.parse::<GameRecord>(),
+            Err(RecordError::ResultMismatch { .. })
+        ));
+    }
+
+    #[test]
+    pub fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("xors-record-{}.txt", std::process::id()));
+        let record = record(&[5, 1, 2]);
+        record.save(&path).unwrap();
+        let loaded = GameRecord::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(record, loaded);
+        assert!(matches!(GameRecord::load(&path), Err(RecordError::Io(_))));
+    }
+}
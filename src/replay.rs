@@ -0,0 +1,169 @@
+use crate::board::*;
+use crate::record::*;
+use crate::GameResult;
+
+pub struct Replay {
+    record: GameRecord,
+    positions: Vec<GameBoard>,
+    current: usize,
+}
+
+impl Replay {
+    pub fn new(record: GameRecord) -> Result<Self, RecordError> {
+        let positions = record.positions()?;
+        Ok(Self {
+            record,
+            positions,
+            current: 0,
+        })
+    }
+
+    pub fn record(&self) -> &GameRecord {
+        &self.record
+    }
+
+    // The number of moves played so far in the position being shown.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn move_count(&self) -> usize {
+        self.record.moves.len()
+    }
+
+    pub fn board(&self) -> &GameBoard {
+        &self.positions[self.current]
+    }
+
+    pub fn step_forward(&mut self) -> bool {
+        self.jump_to(self.current + 1)
+    }
+
+    pub fn step_back(&mut self) -> bool {
+        self.current > 0 && self.jump_to(self.current - 1)
+    }
+
+    pub fn to_first(&mut self) {
+        self.current = 0;
+    }
+
+    pub fn to_last(&mut self) {
+        self.current = self.move_count();
+    }
+
+    pub fn jump_to(&mut self, move_number: usize) -> bool {
+        if move_number > self.move_count() {
+            return false;
+        }
+        self.current = move_number;
+        true
+    }
+
+    pub fn annotation(&self) -> String {
+        if self.current == 0 {
+            return format!(
+                "Start of game: {} ({}) v {} ({})",
+                self.record.player1.id,
+                self.record.player1.token,
+                self.record.player2.id,
+                self.record.player2.token
+            );
+        }
+        let index = self.current - 1;
+        let player = if index.is_multiple_of(2) {
+            &self.record.player1
+        } else {
+            &self.record.player2
+        };
+        let action = match &self.record.moves[index] {
+            Some(location) => format!("plays {}", location.number()),
+            None => "skips their turn".into(),
+        };
+        let mut annotation = format!(
+            "Move {} of {}: {} ({}) {}",
+            self.current,
+            self.move_count(),
+            player.id,
+            player.token,
+            action
+        );
+        if self.current == self.move_count() {
+            match self.record.result().ok().flatten() {
+                Some(GameResult::Player1(_)) => {
+                    annotation += &format!(". {} wins!", self.record.player1.id)
+                }
+                Some(GameResult::Player2(_)) => {
+                    annotation += &format!(". {} wins!", self.record.player2.id)
+                }
+                Some(GameResult::Tie) => annotation += ". It's a tie!",
+                None => annotation += ". Game unfinished.",
+            }
+        }
+        annotation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::RuleSet;
+
+    fn replay() -> Replay {
+        let record = GameRecord {
+            player1: RecordedPlayer {
+                id: "Yasmin".into(),
+                token: BoardToken::Cross,
+            },
+            player2: RecordedPlayer {
+                id: "Mummy".into(),
+                token: BoardToken::Nought,
+            },
+            moves: [5, 1, 2, 4, 8]
+                .into_iter()
+                .map(BoardLocation::from_number)
+                .collect(),
+            started: 0,
+            updated: 0,
+            seed: None,
+            rules: RuleSet::Standard,
+            forfeit: None,
+        };
+        Replay::new(record).unwrap()
+    }
+
+    #[test]
+    pub fn test_stepping() {
+        let mut replay = replay();
+        assert_eq!(0, replay.current());
+        assert_eq!(&GameBoard::default(), replay.board());
+        assert!(!replay.step_back());
+        assert!(replay.step_forward());
+        assert_eq!(
+            Some(BoardToken::Cross),
+            replay.board().get(BoardLocation::MiddleCentre)
+        );
+        assert_eq!("Move 1 of 5: Yasmin (X) plays 5", replay.annotation());
+        assert!(replay.step_forward());
+        assert_eq!("Move 2 of 5: Mummy (O) plays 1", replay.annotation());
+        assert!(replay.step_back());
+        assert_eq!(None, replay.board().get(BoardLocation::TopLeft));
+    }
+
+    #[test]
+    pub fn test_jumping() {
+        let mut replay = replay();
+        replay.to_last();
+        assert_eq!(5, replay.current());
+        assert!(!replay.step_forward());
+        assert_eq!(
+            "Move 5 of 5: Yasmin (X) plays 8. Yasmin wins!",
+            replay.annotation()
+        );
+        assert!(replay.jump_to(3));
+        assert_eq!(6, replay.board().empty_locations().len());
+        assert!(!replay.jump_to(6));
+        assert_eq!(3, replay.current());
+        replay.to_first();
+        assert_eq!("Start of game: Yasmin (X) v Mummy (O)", replay.annotation());
+    }
+}
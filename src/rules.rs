@@ -0,0 +1,127 @@
+use crate::board::*;
+use crate::{GameResult, Turn, Win};
+use std::fmt;
+
+// What completing a line means. At standard tic-tac-toe the player who makes
+// three in a row wins; at misère they lose. A full board without a line is a
+// tie either way.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum RuleSet {
+    #[default]
+    Standard,
+    Misere,
+}
+
+impl RuleSet {
+    pub const ALL: [RuleSet; 2] = [RuleSet::Standard, RuleSet::Misere];
+
+    // Accepts the names shown by `Display`, and misère spelt with its accent.
+    pub fn from_name(name: &str) -> Option<RuleSet> {
+        match name.to_lowercase().as_str() {
+            "standard" => Some(RuleSet::Standard),
+            "misere" | "misère" => Some(RuleSet::Misere),
+            _ => None,
+        }
+    }
+
+    // The token that wins once `completed_by` has made a line.
+    pub fn winner(&self, completed_by: &BoardToken) -> BoardToken {
+        match self {
+            RuleSet::Standard => completed_by.clone(),
+            RuleSet::Misere => completed_by.opponent(),
+        }
+    }
+
+    // The player who wins once `completed_by` has made a line, for boards
+    // where the players share their tokens.
+    pub fn winning_turn(&self, completed_by: Turn) -> Turn {
+        match self {
+            RuleSet::Standard => completed_by,
+            RuleSet::Misere => completed_by.other(),
+        }
+    }
+
+    // The result of the game on `board`, or `None` if it isn't over. A win's
+    // lines are the lines that decided it, whoever made them.
+    pub fn result<B: Board>(&self, board: &B, player1_token: &B::Token) -> Option<GameResult<B>> {
+        if let Some((token, lines)) = board.won_by(*self) {
+            let win = Win {
+                token,
+                lines,
+                forfeit: false,
+            };
+            if &win.token == player1_token {
+                Some(GameResult::Player1(win))
+            } else {
+                Some(GameResult::Player2(win))
+            }
+        } else if board.is_finished() {
+            Some(GameResult::Tie)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for RuleSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleSet::Standard => write!(f, "standard"),
+            RuleSet::Misere => write!(f, "misere"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn top_row_for_crosses() -> GameBoard {
+        let mut board = GameBoard::default();
+        for (location, token) in [
+            (BoardLocation::TopLeft, BoardToken::Cross),
+            (BoardLocation::MiddleLeft, BoardToken::Nought),
+            (BoardLocation::TopCentre, BoardToken::Cross),
+            (BoardLocation::MiddleCentre, BoardToken::Nought),
+            (BoardLocation::TopRight, BoardToken::Cross),
+        ] {
+            board.play(location, token).unwrap();
+        }
+        board
+    }
+
+    #[test]
+    pub fn test_names() {
+        for rules in RuleSet::ALL {
+            assert_eq!(Some(rules), RuleSet::from_name(&rules.to_string()));
+        }
+        assert_eq!(Some(RuleSet::Misere), RuleSet::from_name("Misère"));
+        assert_eq!(None, RuleSet::from_name("reverse"));
+        assert_eq!(RuleSet::Standard, RuleSet::default());
+    }
+
+    #[test]
+    pub fn test_completing_a_line() {
+        let board = top_row_for_crosses();
+        let win = Win {
+            token: BoardToken::Cross,
+            lines: vec![BoardLine::TopRow],
+            forfeit: false,
+        };
+        assert_eq!(
+            Some(GameResult::Player1(win.clone())),
+            RuleSet::Standard.result(&board, &BoardToken::Cross)
+        );
+        assert_eq!(
+            Some(GameResult::Player2(Win {
+                token: BoardToken::Nought,
+                ..win
+            })),
+            RuleSet::Misere.result(&board, &BoardToken::Cross)
+        );
+        assert_eq!(
+            None,
+            RuleSet::Misere.result(&GameBoard::default(), &BoardToken::Cross)
+        );
+    }
+}
@@ -0,0 +1,353 @@
+use crate::board::*;
+use crate::rules::RuleSet;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+type Cells = [Option<BoardToken>; 9];
+
+const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 4, 8],
+    [2, 4, 6],
+];
+
+// Each entry maps a cell of the transformed board to the cell of the original
+// board it is taken from: the four rotations followed by the four reflections.
+pub(crate) const SYMMETRIES: [[usize; 9]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8],
+    [6, 3, 0, 7, 4, 1, 8, 5, 2],
+    [8, 7, 6, 5, 4, 3, 2, 1, 0],
+    [2, 5, 8, 1, 4, 7, 0, 3, 6],
+    [2, 1, 0, 5, 4, 3, 8, 7, 6],
+    [6, 7, 8, 3, 4, 5, 0, 1, 2],
+    [0, 3, 6, 1, 4, 7, 2, 5, 8],
+    [8, 5, 2, 7, 4, 1, 6, 3, 0],
+];
+
+#[derive(Clone, Copy)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Clone, Copy)]
+struct Entry {
+    score: i32,
+    bound: Bound,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SearchStats {
+    pub nodes: u64,
+    pub table_hits: u64,
+    pub elapsed: Duration,
+}
+
+// `score` is from the point of view of the side to move under the engine's
+// rules: positive is a forced win, negative a forced loss and zero a draw.
+// The magnitude is one more than the number of empty cells left when the
+// game ends, so faster wins score higher.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    pub score: i32,
+    pub best_move: Option<BoardLocation>,
+    pub stats: SearchStats,
+}
+
+#[derive(Default)]
+pub struct SearchEngine {
+    table: HashMap<u32, Entry>,
+    stats: SearchStats,
+    rules: RuleSet,
+}
+
+fn cells(board: &GameBoard) -> Cells {
+    let mut cells: Cells = Default::default();
+    for (location, token) in board.cells() {
+        cells[location.index()] = token;
+    }
+    cells
+}
+
+fn winner(cells: &Cells) -> Option<BoardToken> {
+    LINES.iter().find_map(|[a, b, c]| {
+        if cells[*a].is_some() && cells[*a] == cells[*b] && cells[*a] == cells[*c] {
+            cells[*a].clone()
+        } else {
+            None
+        }
+    })
+}
+
+fn empties(cells: &Cells) -> i32 {
+    cells.iter().filter(|cell| cell.is_none()).count() as i32
+}
+
+fn encode(cells: &Cells, symmetry: &[usize; 9]) -> u32 {
+    symmetry.iter().fold(0, |acc, i| {
+        acc * 3
+            + match cells[*i] {
+                None => 0,
+                Some(BoardToken::Cross) => 1,
+                Some(BoardToken::Nought) => 2,
+            }
+    })
+}
+
+fn key(cells: &Cells, to_move: &BoardToken) -> u32 {
+    let canonical = SYMMETRIES
+        .iter()
+        .map(|symmetry| encode(cells, symmetry))
+        .min()
+        .unwrap();
+    canonical * 2
+        + match to_move {
+            BoardToken::Cross => 0,
+            BoardToken::Nought => 1,
+        }
+}
+
+impl SearchEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn new_with_rules(rules: RuleSet) -> Self {
+        Self {
+            rules,
+            ..Self::default()
+        }
+    }
+
+    pub fn rules(&self) -> RuleSet {
+        self.rules
+    }
+
+    pub fn clear(&mut self) {
+        self.table.clear();
+    }
+
+    pub fn table_size(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn search(&mut self, board: &GameBoard, to_move: BoardToken) -> SearchResult {
+        self.stats = SearchStats::default();
+        let start = Instant::now();
+        let mut cells = cells(board);
+        let mut best: Option<(i32, BoardLocation)> = None;
+        let score = if winner(&cells).is_some() || empties(&cells) == 0 {
+            self.negamax(&mut cells, &to_move, -100, 100)
+        } else {
+            let mut alpha = -100;
+            for location in BoardLocation::ALL {
+                if cells[location.index()].is_some() {
+                    continue;
+                }
+                cells[location.index()] = Some(to_move.clone());
+                let score = -self.negamax(&mut cells, &to_move.opponent(), -100, -alpha);
+                cells[location.index()] = None;
+                if best
+                    .as_ref()
+                    .is_none_or(|(best_score, _)| score > *best_score)
+                {
+                    best = Some((score, location));
+                    alpha = alpha.max(score);
+                }
+            }
+            best.as_ref().unwrap().0
+        };
+        self.stats.elapsed = start.elapsed();
+        SearchResult {
+            score,
+            best_move: best.map(|(_, location)| location),
+            stats: self.stats.clone(),
+        }
+    }
+
+    pub fn evaluate_all(&mut self, positions: &[(GameBoard, BoardToken)]) -> Vec<SearchResult> {
+        positions
+            .iter()
+            .map(|(board, to_move)| self.search(board, to_move.clone()))
+            .collect()
+    }
+
+    fn negamax(
+        &mut self,
+        cells: &mut Cells,
+        to_move: &BoardToken,
+        mut alpha: i32,
+        mut beta: i32,
+    ) -> i32 {
+        self.stats.nodes += 1;
+        if let Some(completed_by) = winner(cells) {
+            let score = 1 + empties(cells);
+            return if &self.rules.winner(&completed_by) == to_move {
+                score
+            } else {
+                -score
+            };
+        }
+        if empties(cells) == 0 {
+            return 0;
+        }
+
+        let key = key(cells, to_move);
+        if let Some(entry) = self.table.get(&key) {
+            self.stats.table_hits += 1;
+            match entry.bound {
+                Bound::Exact => return entry.score,
+                Bound::Lower => alpha = alpha.max(entry.score),
+                Bound::Upper => beta = beta.min(entry.score),
+            }
+            if alpha >= beta {
+                return entry.score;
+            }
+        }
+
+        let original_alpha = alpha;
+        let mut best = i32::MIN;
+        for i in 0..cells.len() {
+            if cells[i].is_some() {
+                continue;
+            }
+            cells[i] = Some(to_move.clone());
+            let score = -self.negamax(cells, &to_move.opponent(), -beta, -alpha);
+            cells[i] = None;
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best <= original_alpha {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.insert(key, Entry { score: best, bound });
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board_with(moves: &[(BoardLocation, BoardToken)]) -> GameBoard {
+        let mut board = GameBoard::default();
+        for (location, token) in moves {
+            board.place(location.clone(), token.clone()).unwrap();
+        }
+        board
+    }
+
+    #[test]
+    pub fn test_empty_board_is_a_draw() {
+        let mut engine = SearchEngine::new();
+        let result = engine.search(&GameBoard::default(), BoardToken::Cross);
+        assert_eq!(0, result.score);
+        assert!(result.best_move.is_some());
+        assert!(result.stats.nodes > 0);
+    }
+
+    #[test]
+    pub fn test_finds_immediate_win() {
+        let board = board_with(&[
+            (BoardLocation::TopLeft, BoardToken::Cross),
+            (BoardLocation::MiddleLeft, BoardToken::Nought),
+            (BoardLocation::TopCentre, BoardToken::Cross),
+            (BoardLocation::MiddleCentre, BoardToken::Nought),
+        ]);
+        let mut engine = SearchEngine::new();
+        let result = engine.search(&board, BoardToken::Cross);
+        assert_eq!(Some(BoardLocation::TopRight), result.best_move);
+        assert_eq!(5, result.score);
+    }
+
+    #[test]
+    pub fn test_finished_board_has_no_move() {
+        let board = board_with(&[
+            (BoardLocation::TopLeft, BoardToken::Cross),
+            (BoardLocation::MiddleLeft, BoardToken::Nought),
+            (BoardLocation::TopCentre, BoardToken::Cross),
+            (BoardLocation::MiddleCentre, BoardToken::Nought),
+            (BoardLocation::TopRight, BoardToken::Cross),
+        ]);
+        let mut engine = SearchEngine::new();
+        let result = engine.search(&board, BoardToken::Nought);
+        assert_eq!(None, result.best_move);
+        assert_eq!(-5, result.score);
+    }
+
+    #[test]
+    pub fn test_symmetric_positions_share_table_entries() {
+        let corner = board_with(&[(BoardLocation::TopLeft, BoardToken::Cross)]);
+        let other_corner = board_with(&[(BoardLocation::BottomRight, BoardToken::Cross)]);
+        let mut engine = SearchEngine::new();
+        let first = engine.search(&corner, BoardToken::Nought);
+        let second = engine.search(&other_corner, BoardToken::Nought);
+        assert_eq!(first.score, second.score);
+        assert!(second.stats.table_hits > 0);
+        assert!(second.stats.nodes < first.stats.nodes);
+    }
+
+    #[test]
+    pub fn test_misere() {
+        let mut engine = SearchEngine::new_with_rules(RuleSet::Misere);
+        assert_eq!(RuleSet::Misere, engine.rules());
+        assert_eq!(
+            0,
+            engine
+                .search(&GameBoard::default(), BoardToken::Cross)
+                .score
+        );
+
+        // Taking the top right would complete a line, so crosses must play
+        // elsewhere.
+        let board = board_with(&[
+            (BoardLocation::TopLeft, BoardToken::Cross),
+            (BoardLocation::MiddleLeft, BoardToken::Nought),
+            (BoardLocation::TopCentre, BoardToken::Cross),
+            (BoardLocation::BottomRight, BoardToken::Nought),
+        ]);
+        let result = engine.search(&board, BoardToken::Cross);
+        assert!(result.best_move.is_some());
+        assert_ne!(Some(BoardLocation::TopRight), result.best_move);
+
+        // Having made a line, crosses have lost.
+        let mut lost = board.clone();
+        lost.play(BoardLocation::TopRight, BoardToken::Cross)
+            .unwrap();
+        assert_eq!(5, engine.search(&lost, BoardToken::Nought).score);
+    }
+
+    #[test]
+    pub fn test_evaluate_all() {
+        let positions = vec![
+            (GameBoard::default(), BoardToken::Cross),
+            (
+                board_with(&[
+                    (BoardLocation::TopLeft, BoardToken::Cross),
+                    (BoardLocation::MiddleCentre, BoardToken::Nought),
+                    (BoardLocation::TopCentre, BoardToken::Cross),
+                ]),
+                BoardToken::Nought,
+            ),
+        ];
+        let mut engine = SearchEngine::new();
+        let results = engine.evaluate_all(&positions);
+        assert_eq!(2, results.len());
+        assert_eq!(0, results[0].score);
+        assert_eq!(Some(BoardLocation::TopRight), results[1].best_move);
+    }
+}
@@ -0,0 +1,419 @@
+use crate::board::*;
+use crate::rules::RuleSet;
+use std::fmt;
+use std::str::FromStr;
+
+// Nine boards in a 3x3 grid. Winning a board claims its square on the meta
+// board, and three claimed squares in a row win the game. The cell a move is
+// played in sends the opponent to the board in the same position; if that
+// board is already finished they may play on any board instead. Crosses move
+// first.
+#[derive(Debug, PartialEq, Clone)]
+pub struct UltimateBoard {
+    boards: [GameBoard; 9],
+    meta: GameBoard,
+    next_board: Option<BoardLocation>,
+    to_move: BoardToken,
+}
+
+// A cell on one of the boards. Shown and parsed as the board number followed
+// by the cell number, so 53 is the top right cell of the centre board.
+#[derive(Debug, PartialEq, Clone)]
+pub struct UltimateMove {
+    pub board: BoardLocation,
+    pub cell: BoardLocation,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UltimateError {
+    WrongBoard { required: BoardLocation },
+    BoardFinished(BoardLocation),
+    Occupied(UltimateMove),
+    GameOver,
+    BadMove(String),
+}
+
+impl UltimateMove {
+    pub fn new(board: BoardLocation, cell: BoardLocation) -> Self {
+        Self { board, cell }
+    }
+}
+
+impl Default for UltimateBoard {
+    fn default() -> Self {
+        Self {
+            boards: Default::default(),
+            meta: GameBoard::default(),
+            next_board: None,
+            to_move: BoardToken::Cross,
+        }
+    }
+}
+
+impl UltimateBoard {
+    pub fn board(&self, location: BoardLocation) -> &GameBoard {
+        &self.boards[location.index()]
+    }
+
+    // Holds the token of whoever won each board.
+    pub fn meta(&self) -> &GameBoard {
+        &self.meta
+    }
+
+    // The board the next move has to be played on, or `None` if any unfinished
+    // board will do.
+    pub fn next_board(&self) -> Option<BoardLocation> {
+        self.next_board.clone()
+    }
+
+    pub fn to_move(&self) -> BoardToken {
+        self.to_move.clone()
+    }
+
+    // A board is finished once it has been won or filled up.
+    pub fn is_finished(&self, location: BoardLocation) -> bool {
+        let board = self.board(location);
+        board.winner().is_some() || board.is_full()
+    }
+
+    pub fn winner(&self) -> Option<(BoardToken, BoardLine)> {
+        self.meta.winner()
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.winner().is_some()
+            || BoardLocation::ALL
+                .into_iter()
+                .all(|location| self.is_finished(location))
+    }
+
+    pub fn legal_moves(&self) -> Vec<UltimateMove> {
+        if self.is_over() {
+            return Vec::new();
+        }
+        let boards = match &self.next_board {
+            Some(board) => vec![board.clone()],
+            None => BoardLocation::ALL
+                .into_iter()
+                .filter(|location| !self.is_finished(location.clone()))
+                .collect(),
+        };
+        boards
+            .into_iter()
+            .flat_map(|board| {
+                self.board(board.clone())
+                    .empty_locations()
+                    .into_iter()
+                    .map(move |cell| UltimateMove::new(board.clone(), cell))
+            })
+            .collect()
+    }
+
+    pub fn play(&mut self, mv: &UltimateMove) -> Result<(), UltimateError> {
+        let token = self.to_move.clone();
+        self.play_as(&token, mv)
+    }
+
+    // Plays `token` whoever's turn the board thinks it is.
+    fn play_as(&mut self, token: &BoardToken, mv: &UltimateMove) -> Result<(), UltimateError> {
+        if self.is_over() {
+            return Err(UltimateError::GameOver);
+        }
+        if let Some(required) = &self.next_board {
+            if *required != mv.board {
+                return Err(UltimateError::WrongBoard {
+                    required: required.clone(),
+                });
+            }
+        }
+        if self.is_finished(mv.board.clone()) {
+            return Err(UltimateError::BoardFinished(mv.board.clone()));
+        }
+        let board = &mut self.boards[mv.board.index()];
+        board
+            .place(mv.cell.clone(), token.clone())
+            .map_err(|_| UltimateError::Occupied(mv.clone()))?;
+        if let Some((token, _)) = board.winner() {
+            self.meta
+                .place(mv.board.clone(), token)
+                .expect("a board can only be won once");
+        }
+        self.next_board = if self.is_finished(mv.cell.clone()) {
+            None
+        } else {
+            Some(mv.cell.clone())
+        };
+        self.to_move = token.opponent();
+        Ok(())
+    }
+}
+
+impl Board for UltimateBoard {
+    type Token = BoardToken;
+    type Move = UltimateMove;
+    // Lines of boards on the meta board.
+    type Line = BoardLine;
+    type Error = UltimateError;
+
+    fn legal_moves(&self, _token: &BoardToken) -> Vec<UltimateMove> {
+        UltimateBoard::legal_moves(self)
+    }
+
+    fn play_move(&mut self, token: &BoardToken, mv: &UltimateMove) -> Result<(), UltimateError> {
+        self.play_as(token, mv)
+    }
+
+    fn won_by(&self, rules: RuleSet) -> Option<(BoardToken, Vec<BoardLine>)> {
+        self.meta.won_by(rules)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.is_over()
+    }
+
+    fn move_prompt(&self, _token: &BoardToken) -> String {
+        match &self.next_board {
+            Some(board) => format!("Enter a cell on board {0} ({0}1-{0}9)", board.number()),
+            None => "Enter a board and cell (11-99)".into(),
+        }
+    }
+}
+
+impl fmt::Display for UltimateMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.board.number(), self.cell.number())
+    }
+}
+
+impl FromStr for UltimateMove {
+    type Err = UltimateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let bad = || UltimateError::BadMove(s.into());
+        let digits: Vec<u32> = s
+            .chars()
+            .filter(|c| !matches!(c, ' ' | ','))
+            .map(|c| c.to_digit(10).ok_or_else(bad))
+            .collect::<Result<_, _>>()?;
+        match digits[..] {
+            [board, cell] => Ok(UltimateMove::new(
+                BoardLocation::from_number(board).ok_or_else(bad)?,
+                BoardLocation::from_number(cell).ok_or_else(bad)?,
+            )),
+            _ => Err(bad()),
+        }
+    }
+}
+
+impl fmt::Display for UltimateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UltimateError::WrongBoard { required } => {
+                write!(f, "the move has to be on the {} board", required)
+            }
+            UltimateError::BoardFinished(board) => {
+                write!(f, "the {} board is already finished", board)
+            }
+            UltimateError::Occupied(mv) => write!(
+                f,
+                "{} on the {} board is already occupied",
+                mv.cell, mv.board
+            ),
+            UltimateError::GameOver => write!(f, "the game is already over"),
+            UltimateError::BadMove(s) => {
+                write!(f, "{:?} is not a board and cell such as 53", s)
+            }
+        }
+    }
+}
+
+impl std::error::Error for UltimateError {}
+
+// The nine boards as one 9x9 grid, followed by who has won each board: # for
+// a board that was filled without a winner.
+impl fmt::Display for UltimateBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..9 {
+            if row > 0 && row % 3 == 0 {
+                writeln!(f, "-------+-------+-------")?;
+            }
+            for column in 0..9 {
+                if column > 0 && column % 3 == 0 {
+                    write!(f, " |")?;
+                }
+                let board = BoardLocation::ALL[row / 3 * 3 + column / 3].clone();
+                let cell = BoardLocation::ALL[row % 3 * 3 + column % 3].clone();
+                let c = match self.board(board).get(cell) {
+                    Some(token) => token.to_string(),
+                    None => ".".into(),
+                };
+                write!(f, " {}", c)?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f)?;
+        for row in 0..3 {
+            write!(f, "{}", if row == 0 { "Boards " } else { "       " })?;
+            for column in 0..3 {
+                let board = BoardLocation::ALL[row * 3 + column].clone();
+                let c = match self.meta.get(board.clone()) {
+                    Some(token) => token.to_string(),
+                    None if self.is_finished(board) => "#".into(),
+                    None => ".".into(),
+                };
+                write!(f, " {}", c)?;
+            }
+            writeln!(f)?;
+        }
+        match (&self.next_board, self.is_over()) {
+            (_, true) => Ok(()),
+            (Some(board), false) => writeln!(f, "Next: the {} board", board),
+            (None, false) => writeln!(f, "Next: any board"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::{RandomPlayer, ScriptedPlayer};
+    use crate::{Game, GameResult};
+
+    fn moves(names: &[&str]) -> Vec<UltimateMove> {
+        names.iter().map(|name| name.parse().unwrap()).collect()
+    }
+
+    fn play(names: &[&str]) -> UltimateBoard {
+        let mut board = UltimateBoard::default();
+        for mv in moves(names) {
+            board.play(&mv).unwrap();
+        }
+        board
+    }
+
+    #[test]
+    pub fn test_move_names() {
+        let mv = UltimateMove::new(BoardLocation::MiddleCentre, BoardLocation::TopRight);
+        assert_eq!("53", mv.to_string());
+        assert_eq!(Ok(mv.clone()), "53".parse());
+        assert_eq!(Ok(mv.clone()), "5 3".parse());
+        assert_eq!(Ok(mv), " 5,3\n".parse());
+        assert!("5".parse::<UltimateMove>().is_err());
+        assert!("50".parse::<UltimateMove>().is_err());
+        assert!("5x".parse::<UltimateMove>().is_err());
+        assert!("".parse::<UltimateMove>().is_err());
+    }
+
+    #[test]
+    pub fn test_cell_sends_opponent_to_board() {
+        let mut board = play(&["53"]);
+        assert_eq!(Some(BoardLocation::TopRight), board.next_board());
+        assert_eq!(BoardToken::Nought, board.to_move());
+        assert_eq!(9, board.legal_moves().len());
+        assert_eq!(
+            Err(UltimateError::WrongBoard {
+                required: BoardLocation::TopRight
+            }),
+            board.play(&"11".parse().unwrap())
+        );
+        board.play(&"35".parse().unwrap()).unwrap();
+        assert_eq!(
+            Err(UltimateError::Occupied("53".parse().unwrap())),
+            board.play(&"53".parse().unwrap())
+        );
+    }
+
+    #[test]
+    pub fn test_winning_a_board_claims_it() {
+        // Noughts keep sending crosses back to board 1, where they take the
+        // top row.
+        let mut board = play(&["12", "21", "13", "31"]);
+        assert_eq!(None, board.meta().get(BoardLocation::TopLeft));
+        board.play(&"11".parse().unwrap()).unwrap();
+        assert_eq!(
+            Some(BoardToken::Cross),
+            board.meta().get(BoardLocation::TopLeft)
+        );
+        assert!(board.is_finished(BoardLocation::TopLeft));
+        assert_eq!(None, board.winner());
+    }
+
+    #[test]
+    pub fn test_sent_to_a_finished_board_is_a_free_move() {
+        let mut board = play(&["12", "21", "13", "31", "11"]);
+        assert_eq!(None, board.next_board());
+        assert_eq!(70, board.legal_moves().len());
+        assert!(board
+            .legal_moves()
+            .iter()
+            .all(|mv| mv.board != BoardLocation::TopLeft));
+        assert_eq!(
+            Err(UltimateError::BoardFinished(BoardLocation::TopLeft)),
+            board.play(&"19".parse().unwrap())
+        );
+        board.play(&"41".parse().unwrap()).unwrap();
+        assert_eq!(None, board.next_board());
+    }
+
+    #[test]
+    pub fn test_three_boards_in_a_row_wins() {
+        // Crosses take the middle row of boards 1, 2 and 3, while noughts win
+        // boards 4 and 5 but are a board short.
+        let mut game = Game::new_with_board(UltimateBoard::default());
+        game.set_verbose(false);
+        let crosses = moves(&["14", "15", "16", "24", "25", "26", "34", "35", "36"]);
+        let noughts = moves(&["41", "51", "62", "42", "52", "63", "43", "53"]);
+        game.register_player(ScriptedPlayer::new("Yasmin", BoardToken::Cross, &crosses))
+            .unwrap();
+        game.register_player(ScriptedPlayer::new("Mummy", BoardToken::Nought, &noughts))
+            .unwrap();
+        assert!(matches!(game.play().unwrap(), GameResult::Player1(_)));
+        let state = game.board();
+        assert_eq!(Some((BoardToken::Cross, BoardLine::TopRow)), state.winner());
+        assert_eq!(
+            Some(BoardToken::Nought),
+            state.meta().get(BoardLocation::MiddleCentre)
+        );
+        assert!(state.legal_moves().is_empty());
+        assert_eq!(17, game.history().len());
+    }
+
+    #[test]
+    pub fn test_random_players_finish() {
+        for seed in 0..5 {
+            let mut game = Game::new_with_board(UltimateBoard::default());
+            game.set_verbose(false);
+            let mut player1 = RandomPlayer::new("one", BoardToken::Cross);
+            player1.set_seed(seed);
+            let mut player2 = RandomPlayer::new("two", BoardToken::Nought);
+            player2.set_seed(seed + 100);
+            game.register_player(player1).unwrap();
+            game.register_player(player2).unwrap();
+            let result = game.play().unwrap();
+            let state = game.board();
+            assert!(state.is_over());
+            match result {
+                GameResult::Player1(_) => {
+                    assert_eq!(BoardToken::Cross, state.winner().unwrap().0)
+                }
+                GameResult::Player2(_) => {
+                    assert_eq!(BoardToken::Nought, state.winner().unwrap().0)
+                }
+                GameResult::Tie => assert_eq!(None, state.winner()),
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_display() {
+        let board = play(&["12", "21", "13", "31", "11", "53"]);
+        let shown = board.to_string();
+        let lines: Vec<&str> = shown.lines().collect();
+        assert_eq!(" X X X | O . . | O . .", lines[0]);
+        assert_eq!("-------+-------+-------", lines[3]);
+        assert_eq!(" . . . | . . O | . . .", lines[4]);
+        assert_eq!("Boards  X . .", lines[12]);
+        assert_eq!("Next: the top right (3) board", lines[15]);
+    }
+}
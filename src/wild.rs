@@ -0,0 +1,269 @@
+use crate::board::*;
+use crate::player::PlayerError;
+use crate::rules::RuleSet;
+use crate::Turn;
+use std::fmt;
+use std::str::FromStr;
+
+// Tic-tac-toe where either player may place either token, and whoever
+// completes a line wins whichever token it is in. Player1 moves first.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Wild {
+    board: GameBoard,
+    to_move: Turn,
+}
+
+// Shown and parsed as the location number followed by the token, such as 5x.
+#[derive(Debug, PartialEq, Clone)]
+pub struct WildMove {
+    pub location: BoardLocation,
+    pub token: BoardToken,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum WildError {
+    Occupied(BoardLocation),
+    GameOver,
+}
+
+impl WildMove {
+    pub fn new(location: BoardLocation, token: BoardToken) -> Self {
+        Self { location, token }
+    }
+}
+
+impl Default for Wild {
+    fn default() -> Self {
+        Self {
+            board: GameBoard::default(),
+            to_move: Turn::Player1,
+        }
+    }
+}
+
+impl Wild {
+    pub fn board(&self) -> &GameBoard {
+        &self.board
+    }
+
+    pub fn to_move(&self) -> Turn {
+        self.to_move
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.board.winner().is_some() || self.board.is_full()
+    }
+
+    pub fn legal_moves(&self) -> Vec<WildMove> {
+        if self.is_over() {
+            return Vec::new();
+        }
+        self.board
+            .empty_locations()
+            .into_iter()
+            .flat_map(|location| {
+                [BoardToken::Cross, BoardToken::Nought]
+                    .map(|token| WildMove::new(location.clone(), token))
+            })
+            .collect()
+    }
+}
+
+// Either player may play either token, so the players' tokens are their
+// turns.
+impl Board for Wild {
+    type Token = Turn;
+    type Move = WildMove;
+    type Line = BoardLine;
+    type Error = WildError;
+
+    fn legal_moves(&self, _turn: &Turn) -> Vec<WildMove> {
+        Wild::legal_moves(self)
+    }
+
+    fn play_move(&mut self, turn: &Turn, mv: &WildMove) -> Result<(), WildError> {
+        if self.is_over() {
+            return Err(WildError::GameOver);
+        }
+        self.board
+            .place(mv.location.clone(), mv.token.clone())
+            .map_err(|_| WildError::Occupied(mv.location.clone()))?;
+        self.to_move = turn.other();
+        Ok(())
+    }
+
+    // A line can only have been made by the last move, so it was made by
+    // whoever played it.
+    fn won_by(&self, rules: RuleSet) -> Option<(Turn, Vec<BoardLine>)> {
+        let lines = self.board.winning_lines();
+        if lines.is_empty() {
+            return None;
+        }
+        Some((rules.winning_turn(self.to_move.other()), lines))
+    }
+
+    fn is_finished(&self) -> bool {
+        self.board.is_full()
+    }
+
+    fn move_prompt(&self, _turn: &Turn) -> String {
+        "Enter a location and token (e.g. 5x or 5o)".into()
+    }
+}
+
+impl fmt::Display for WildMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let token = match self.token {
+            BoardToken::Cross => 'x',
+            BoardToken::Nought => 'o',
+        };
+        write!(f, "{}{}", self.location.number(), token)
+    }
+}
+
+impl FromStr for WildMove {
+    type Err = PlayerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (number, token) = s
+            .char_indices()
+            .last()
+            .map(|(index, token)| (&s[..index], token))
+            .ok_or(PlayerError::InvalidLocation)?;
+        let token = match token.to_ascii_lowercase() {
+            'x' => BoardToken::Cross,
+            'o' => BoardToken::Nought,
+            _ => return Err(PlayerError::InvalidLocation),
+        };
+        let location = number
+            .trim_end_matches([' ', ','])
+            .parse()
+            .ok()
+            .and_then(BoardLocation::from_number)
+            .ok_or(PlayerError::InvalidLocation)?;
+        Ok(WildMove::new(location, token))
+    }
+}
+
+impl fmt::Display for WildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WildError::Occupied(location) => write!(f, "{} is already occupied", location),
+            WildError::GameOver => write!(f, "the game is already over"),
+        }
+    }
+}
+
+impl std::error::Error for WildError {}
+
+impl fmt::Display for Wild {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::RandomPlayer;
+    use crate::{Game, GameError, GameResult};
+
+    fn winner(wild: &Wild) -> Option<Turn> {
+        wild.won_by(RuleSet::Standard).map(|(turn, _)| turn)
+    }
+
+    fn moves(names: &[&str]) -> Vec<WildMove> {
+        names.iter().map(|name| name.parse().unwrap()).collect()
+    }
+
+    fn play(names: &[&str]) -> Wild {
+        let mut wild = Wild::default();
+        for mv in moves(names) {
+            wild.play_move(&wild.to_move(), &mv).unwrap();
+        }
+        wild
+    }
+
+    #[test]
+    pub fn test_move_names() {
+        let mv = WildMove::new(BoardLocation::MiddleCentre, BoardToken::Nought);
+        assert_eq!("5o", mv.to_string());
+        assert_eq!(Ok(mv.clone()), "5o".parse());
+        assert_eq!(Ok(mv.clone()), "5 O".parse());
+        assert_eq!(Ok(mv), " 5,o\n".parse());
+        assert_eq!(
+            Ok(WildMove::new(BoardLocation::TopLeft, BoardToken::Cross)),
+            "1X".parse()
+        );
+        assert!("5".parse::<WildMove>().is_err());
+        assert!("0x".parse::<WildMove>().is_err());
+        assert!("5z".parse::<WildMove>().is_err());
+        assert!("".parse::<WildMove>().is_err());
+    }
+
+    #[test]
+    pub fn test_the_mover_wins() {
+        // Player2 finishes the line of noughts that player1 started.
+        let wild = play(&["1o", "5x", "2o"]);
+        assert_eq!(None, winner(&wild));
+        assert_eq!(12, wild.legal_moves().len());
+        let mut wild = play(&["1o", "5x", "2o", "3o"]);
+        assert_eq!(Some(Turn::Player2), winner(&wild));
+        assert_eq!(
+            Some((Turn::Player1, vec![BoardLine::TopRow])),
+            wild.won_by(RuleSet::Misere)
+        );
+        assert!(wild.legal_moves().is_empty());
+        assert_eq!(
+            Err(WildError::GameOver),
+            wild.play_move(&wild.to_move(), &"9x".parse().unwrap())
+        );
+
+        let wild = play(&["1x", "5o", "9x", "3o", "7o"]);
+        assert_eq!(Some(Turn::Player1), winner(&wild));
+    }
+
+    #[test]
+    pub fn test_occupied_and_tied() {
+        let mut wild = play(&["5x"]);
+        assert_eq!(
+            Err(WildError::Occupied(BoardLocation::MiddleCentre)),
+            wild.play_move(&wild.to_move(), &"5o".parse().unwrap())
+        );
+        let wild = play(&["1x", "2o", "3x", "4x", "5o", "6o", "7o", "8x", "9x"]);
+        assert_eq!(None, winner(&wild));
+        assert!(wild.is_finished());
+    }
+
+    #[test]
+    pub fn test_players_need_different_turns() {
+        let mut game = Game::new_with_board(Wild::default());
+        game.register_player(RandomPlayer::new("one", Turn::Player1))
+            .unwrap();
+        assert_eq!(
+            Err(GameError::DuplicateToken(Turn::Player1)),
+            game.register_player(RandomPlayer::new("two", Turn::Player1))
+        );
+    }
+
+    #[test]
+    pub fn test_random_players() {
+        let mut game = Game::new_with_board(Wild::default());
+        game.set_verbose(false);
+        let mut player1 = RandomPlayer::new("one", Turn::Player1);
+        player1.set_seed(1);
+        let mut player2 = RandomPlayer::new("two", Turn::Player2);
+        player2.set_seed(2);
+        game.register_player(player1).unwrap();
+        game.register_player(player2).unwrap();
+        let result = game.play().unwrap();
+        let state = game.board();
+        // The winner made the last move.
+        match result {
+            GameResult::Player1(_) => assert!(game.history().len() % 2 == 1),
+            GameResult::Player2(_) => assert!(game.history().len() % 2 == 0),
+            GameResult::Tie => assert!(state.board().is_full()),
+        }
+    }
+}
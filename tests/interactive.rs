@@ -0,0 +1,395 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+use xors::board::*;
+use xors::event::GameEvent;
+use xors::player::*;
+use xors::*;
+
+// An output that the game and its players can share, and that the test can
+// read back once the game is over.
+#[derive(Clone, Default)]
+struct Screen(Rc<RefCell<Vec<u8>>>);
+
+impl Screen {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).unwrap()
+    }
+}
+
+impl Write for Screen {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn human(id: &str, token: BoardToken, input: &'static str, screen: &Screen) -> impl Player {
+    InteractivePlayer::with_io(id, token, input.as_bytes(), screen.clone())
+}
+
+fn game(screen: &Screen) -> Game {
+    let mut game = Game::new();
+    game.set_output(screen.clone());
+    game
+}
+
+#[test]
+fn test_two_humans() {
+    let screen = Screen::default();
+    let mut game = game(&screen);
+    game.register_player(human("Yasmin", BoardToken::Cross, "5\n2\n8\n", &screen))
+        .unwrap();
+    game.register_player(human("Mummy", BoardToken::Nought, "1\n4\n", &screen))
+        .unwrap();
+    let result = game.play().unwrap();
+    assert!(matches!(result, GameResult::Player1(_)));
+
+    let output = screen.contents();
+    assert!(output.starts_with("     |     |     \n"));
+    assert_eq!(3, output.matches("Yasmin, it's your turn!").count());
+    assert_eq!(2, output.matches("Mummy, it's your turn!").count());
+    assert_eq!(
+        5,
+        output
+            .matches("Enter a location (1-9), u to undo or r to redo: ")
+            .count()
+    );
+    assert!(output.ends_with("Yasmin wins!\n"));
+}
+
+#[test]
+fn test_bad_input_and_occupied_locations() {
+    let screen = Screen::default();
+    let mut game = game(&screen);
+    game.register_player(human(
+        "Yasmin",
+        BoardToken::Cross,
+        "five\n5\n1\n3\n7\n",
+        &screen,
+    ))
+    .unwrap();
+    game.register_player(ScriptedPlayer::new(
+        "Mummy",
+        BoardToken::Nought,
+        &[BoardLocation::TopLeft, BoardLocation::BottomCentre],
+    ))
+    .unwrap();
+    let result = game.play().unwrap();
+    assert!(matches!(result, GameResult::Player1(_)));
+
+    let output = screen.contents();
+    assert!(output.contains("Sorry. Didn't understand that. Try again.\n"));
+    assert!(output.contains("Sorry, top left (1) is already occupied.\nChoose another.\n"));
+    let locations: Vec<u32> = game.history().iter().map(|m| m.location.number()).collect();
+    assert_eq!(vec![5, 1, 3, 8, 7], locations);
+}
+
+#[test]
+fn test_undo_and_redo() {
+    let screen = Screen::default();
+    let mut game = game(&screen);
+    game.register_player(human(
+        "Yasmin",
+        BoardToken::Cross,
+        "u\n1\nu\nr\n2\n",
+        &screen,
+    ))
+    .unwrap();
+    game.register_player(human("Mummy", BoardToken::Nought, "5\n9\n", &screen))
+        .unwrap();
+    game.set_exhausted_policy(MovePolicy::Forfeit);
+    let result = game.play().unwrap();
+
+    // Yasmin runs out of input after taking back and replaying her first
+    // move, and forfeits on her third turn.
+    assert!(matches!(
+        result,
+        GameResult::Player2(Win { forfeit: true, .. })
+    ));
+    let output = screen.contents();
+    assert!(output.contains("Nothing to undo.\n"));
+    assert!(output.contains("Yasmin has run out of moves.\nThat's a forfeit.\n"));
+    let locations: Vec<u32> = game.history().iter().map(|m| m.location.number()).collect();
+    assert_eq!(vec![1, 5, 2, 9], locations);
+}
+
+#[test]
+fn test_undo_before_moving() {
+    let screen = Screen::default();
+    let mut game = game(&screen);
+    game.register_player(human("Yasmin", BoardToken::Cross, "5\n2\n8\n", &screen))
+        .unwrap();
+    game.register_player(human("Mummy", BoardToken::Nought, "u\n1\n4\n", &screen))
+        .unwrap();
+    let result = game.play().unwrap();
+    assert!(matches!(result, GameResult::Player1(_)));
+
+    // Mummy hasn't moved yet, so Yasmin's move is left alone.
+    let output = screen.contents();
+    assert!(output.contains("Nothing to undo.\n"));
+    let locations: Vec<u32> = game.history().iter().map(|m| m.location.number()).collect();
+    assert_eq!(vec![5, 1, 2, 4, 8], locations);
+}
+
+#[test]
+fn test_end_of_input_forfeits() {
+    let screen = Screen::default();
+    let mut game = InteractiveGame::new();
+    game.set_output(screen.clone());
+    game.register_player(InteractivePlayer::with_io(
+        "Yasmin",
+        BoardToken::Cross,
+        "".as_bytes(),
+        screen.clone(),
+    ))
+    .unwrap();
+    game.register_player(InteractivePlayer::with_io(
+        "Mummy",
+        BoardToken::Nought,
+        "".as_bytes(),
+        screen.clone(),
+    ))
+    .unwrap();
+    assert!(matches!(
+        game.play().unwrap(),
+        GameResult::Player2(Win { forfeit: true, .. })
+    ));
+    assert!(screen.contents().ends_with("Mummy wins!\n"));
+}
+
+#[test]
+fn test_human_against_ai() {
+    let screen = Screen::default();
+    let mut game = RandomGame::new_with_difficulty(Difficulty::Perfect);
+    game.set_output(screen.clone());
+    game.register_player(InteractivePlayer::with_io(
+        "Yasmin",
+        BoardToken::Cross,
+        "1\n2\n3\n4\n5\n6\n7\n8\n9\n".as_bytes(),
+        screen.clone(),
+    ))
+    .unwrap();
+    // Playing the cells in order never beats a perfect player.
+    assert!(!matches!(game.play().unwrap(), GameResult::Player1(_)));
+    assert!(screen.contents().contains("Perfect AI, it's your turn!\n"));
+}
+
+#[test]
+fn test_seeded_random_game() {
+    // The moves played in a game against a seeded random player.
+    let play = || {
+        let mut game = RandomGame::new().with_seed(11);
+        game.set_output(io::sink());
+        let moves = Rc::new(RefCell::new(Vec::new()));
+        let log = Rc::clone(&moves);
+        game.add_observer(move |event: &GameEvent| {
+            if let GameEvent::MovePlayed { played, .. } = event {
+                log.borrow_mut().push(played.location.clone());
+            }
+        });
+        game.register_player(InteractivePlayer::with_io(
+            "Yasmin",
+            BoardToken::Cross,
+            "1\n2\n3\n4\n5\n6\n7\n8\n9\n".as_bytes(),
+            io::sink(),
+        ))
+        .unwrap();
+        game.play().unwrap();
+        moves.take()
+    };
+    assert_eq!(play(), play());
+}
+
+#[test]
+fn test_human_against_random() {
+    let screen = Screen::default();
+    let mut game = RandomGame::new();
+    game.set_output(screen.clone());
+    let illegal = Rc::new(RefCell::new(Vec::new()));
+    let log = Rc::clone(&illegal);
+    game.add_observer(move |event: &GameEvent| {
+        if let GameEvent::IllegalMove { turn, .. } = event {
+            log.borrow_mut().push(*turn);
+        }
+    });
+    game.register_player(InteractivePlayer::with_io(
+        "Yasmin",
+        BoardToken::Cross,
+        "5\n1\n2\n3\n4\n6\n7\n8\n9\n".as_bytes(),
+        screen.clone(),
+    ))
+    .unwrap();
+    game.play().unwrap();
+    // The random player only ever picks free locations.
+    assert!(illegal.borrow().iter().all(|turn| *turn == Turn::Player1));
+    assert!(screen.contents().contains("random, it's your turn!\n"));
+}
+
+#[test]
+fn test_grid_game() {
+    use xors::grid::{Cell, Grid};
+
+    let screen = Screen::default();
+    let mut game = Game::new_with_board(Grid::new(4, 4, 4).unwrap());
+    game.set_output(screen.clone());
+    game.register_player(InteractivePlayer::with_io(
+        "Yasmin",
+        BoardToken::Cross,
+        "a1\nA1\nb1\nzz\nc1\nd1\n".as_bytes(),
+        screen.clone(),
+    ))
+    .unwrap();
+    game.register_player(InteractivePlayer::with_io(
+        "Mummy",
+        BoardToken::Nought,
+        "a2\nb2\nE2\nc2\n".as_bytes(),
+        screen.clone(),
+    ))
+    .unwrap();
+    let result = game.play().unwrap();
+    assert!(matches!(result, GameResult::Player1(_)));
+
+    let output = screen.contents();
+    assert!(output.starts_with("   A B C D\n 1 . . . .\n"));
+    assert!(output.contains("Enter a cell (A1-D4), u to undo or r to redo: "));
+    assert!(output.contains("Sorry, A1 is already occupied.\nChoose another.\n"));
+    assert!(output.contains("Sorry, E2 is off the board.\nChoose another.\n"));
+    assert!(output.contains("Sorry. Didn't understand that. Try again.\n"));
+    assert!(output.contains(" 1 X X X X\n"));
+    assert!(output.ends_with("Yasmin wins!\n"));
+    assert_eq!(Cell::new(0, 3), game.history().last().unwrap().location);
+}
+
+#[test]
+fn test_ultimate_game() {
+    use xors::ultimate::UltimateBoard;
+
+    let screen = Screen::default();
+    let mut game = Game::new_with_board(UltimateBoard::default());
+    game.set_output(screen.clone());
+    game.register_player(InteractivePlayer::with_io(
+        "Yasmin",
+        BoardToken::Cross,
+        "14\n15\n16\n99\n24\n25\n26\n34\n35\n36\n".as_bytes(),
+        screen.clone(),
+    ))
+    .unwrap();
+    game.register_player(InteractivePlayer::with_io(
+        "Mummy",
+        BoardToken::Nought,
+        "11\n41\n5 1\n62\n42\nxx\n52\n63\n43\n53\n".as_bytes(),
+        screen.clone(),
+    ))
+    .unwrap();
+    assert!(matches!(game.play().unwrap(), GameResult::Player1(_)));
+
+    let output = screen.contents();
+    assert!(output.contains("Enter a board and cell (11-99), u to undo or r to redo: "));
+    assert!(output.contains("Enter a cell on board 4 (41-49), u to undo or r to redo: "));
+    assert!(output
+        .contains("Sorry, the move has to be on the middle left (4) board.\nChoose another.\n"));
+    assert!(output.contains("Sorry, the move has to be on the top centre (2) board."));
+    assert!(output.contains("Sorry. Didn't understand that. Try again.\n"));
+    assert!(output.contains("Boards  X X X\n        O O .\n"));
+    assert!(output.ends_with("Yasmin wins!\n"));
+    assert_eq!(17, game.history().len());
+}
+
+#[test]
+fn test_notakto_game() {
+    use xors::notakto::Notakto;
+
+    let screen = Screen::default();
+    let mut game = Game::new_with_board(Notakto::new(1).unwrap());
+    game.set_output(screen.clone());
+    game.register_player(InteractivePlayer::with_io(
+        "Yasmin",
+        Turn::Player1,
+        "5\n1\n".as_bytes(),
+        screen.clone(),
+    ))
+    .unwrap();
+    game.register_player(InteractivePlayer::with_io(
+        "Mummy",
+        Turn::Player2,
+        "5\n9\n".as_bytes(),
+        screen.clone(),
+    ))
+    .unwrap();
+    // Yasmin completes the diagonal through Mummy's cross, and so loses.
+    assert!(matches!(game.play().unwrap(), GameResult::Player2(_)));
+
+    let output = screen.contents();
+    assert!(output.contains("Enter a cell (1-9), u to undo or r to redo: "));
+    assert!(output.contains("Sorry, middle centre (5) on board 1 is already occupied."));
+    assert!(output.contains("1 dead\nX . .\n. X .\n. . X\n"));
+    assert!(output.ends_with("Mummy wins!\n"));
+}
+
+#[test]
+fn test_wild_game() {
+    use xors::wild::Wild;
+
+    let screen = Screen::default();
+    let mut game = Game::new_with_board(Wild::default());
+    game.set_output(screen.clone());
+    game.register_player(InteractivePlayer::with_io(
+        "Yasmin",
+        Turn::Player1,
+        "1o\n2\n2o\n".as_bytes(),
+        screen.clone(),
+    ))
+    .unwrap();
+    game.register_player(InteractivePlayer::with_io(
+        "Mummy",
+        Turn::Player2,
+        "1x\n5x\n3O\n".as_bytes(),
+        screen.clone(),
+    ))
+    .unwrap();
+    // Mummy finishes Yasmin's row of noughts, and so wins.
+    assert!(matches!(game.play().unwrap(), GameResult::Player2(_)));
+
+    let output = screen.contents();
+    assert!(output.contains("Enter a location and token (e.g. 5x or 5o), u to undo or r to redo: "));
+    assert!(output.contains("Sorry, top left (1) is already occupied."));
+    assert!(output.contains("Sorry. Didn't understand that. Try again."));
+    assert!(output.ends_with("Mummy wins!\n"));
+}
+
+#[test]
+fn test_numerical_game() {
+    use xors::numerical::Numerical;
+
+    let screen = Screen::default();
+    let mut game = Game::new_with_board(Numerical::default());
+    game.set_output(screen.clone());
+    game.register_player(InteractivePlayer::with_io(
+        "Yasmin",
+        Turn::Player1,
+        "4 1\n1 2\n9 3\n".as_bytes(),
+        screen.clone(),
+    ))
+    .unwrap();
+    game.register_player(InteractivePlayer::with_io(
+        "Mummy",
+        Turn::Player2,
+        "1 8\n5 8\n7 6\n".as_bytes(),
+        screen.clone(),
+    ))
+    .unwrap();
+    // Mummy makes 8 + 1 + 6 down the left column, using Yasmin's 1.
+    assert!(matches!(game.play().unwrap(), GameResult::Player2(_)));
+
+    let output = screen.contents();
+    assert!(output
+        .contains("Enter a cell and a number from 1 3 5 7 9 (e.g. 5 1), u to undo or r to redo: "));
+    assert!(output.contains("Sorry, 2 isn't yours, you play the odd numbers."));
+    assert!(output.contains("Sorry, 8 has already been played."));
+    assert!(output.contains("Odd left: 5 7 9\nEven left: 2 4\n"));
+    assert!(output.ends_with("Mummy wins!\n"));
+}